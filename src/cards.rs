@@ -1,5 +1,6 @@
 use rand::Rng;
-use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use std::iter::repeat;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -28,7 +29,7 @@ pub enum CardColor {
     PuddingPink,
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub enum Card {
     Nigiri(usize),       // 1 -> egg (4 cards), 2 -> salmon (5 cards), 3 -> squid (3 cards)
     Maki(usize),         // 1 -> 4 cards, 2 -> 5 cards, 3 -> 3 cards
@@ -66,14 +67,171 @@ pub enum Card {
 
 use Card::*;
 
+// Party menu categories, a menu has 1 nigiri, 1 roll, 3 appetizers, 2 specials and 1 dessert.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum CardCategory {
+    Nigiri,
+    Rolls,
+    Appetizers,
+    Specials,
+    Desserts,
+}
+
+impl CardCategory {
+    pub const ALL: [CardCategory; 5] = [
+        CardCategory::Nigiri,
+        CardCategory::Rolls,
+        CardCategory::Appetizers,
+        CardCategory::Specials,
+        CardCategory::Desserts,
+    ];
+
+    // Number of items of the category on a Party menu.
+    pub fn get_menu_items_count(self) -> usize {
+        match self {
+            CardCategory::Appetizers => 3,
+            CardCategory::Specials => 2,
+            _ => 1,
+        }
+    }
+
+    // One card for each item of the category.
+    pub fn get_items(self) -> Vec<Card> {
+        match self {
+            CardCategory::Nigiri => vec![Nigiri(1)],
+            CardCategory::Rolls => vec![Maki(1), Temaki, Uramaki(3)],
+            CardCategory::Appetizers => vec![
+                Dumpling,
+                Edamame,
+                Eel,
+                Onigiri(false, false),
+                MisoSoup,
+                Sashimi,
+                Tempura,
+                Tofu,
+            ],
+            CardCategory::Specials => vec![
+                Chopsticks(1),
+                Menu(7),
+                SoySauce,
+                Spoon(4),
+                SpecialOrder,
+                TakeoutBox(10),
+                Tea,
+                Wasabi,
+            ],
+            CardCategory::Desserts => vec![GreenTeaIceCream, FRUIT_CARDS[0], Pudding],
+        }
+    }
+
+    // Every card variant of the category.
+    pub fn get_cards(self) -> Vec<Card> {
+        self.get_items()
+            .into_iter()
+            .flat_map(|card| card.get_variants())
+            .collect()
+    }
+}
+
+pub const FRUIT_CARDS: [Card; 6] = [
+    Fruit(2, 0, 0),
+    Fruit(0, 2, 0),
+    Fruit(0, 0, 2),
+    Fruit(1, 1, 0),
+    Fruit(1, 0, 1),
+    Fruit(0, 1, 1),
+];
+
+pub fn get_dessert_count(players: usize, round: usize) -> usize {
+    match (players, round) {
+        (2..=5, 1) => 5,
+        (2..=5, 2) => 3,
+        (2..=5, 3) => 2,
+        (6..=8, 1) => 7,
+        (6..=8, 2) => 5,
+        (6..=8, 3) => 3,
+        _ => {
+            panic!(
+                "Invalid players count ({:?}) or round ({:?})!",
+                players, round
+            );
+        }
+    }
+}
+
 impl Card {
+    pub fn category(self) -> CardCategory {
+        match self {
+            Nigiri(_) => CardCategory::Nigiri,
+            Maki(_) | Temaki | Uramaki(_) => CardCategory::Rolls,
+            Dumpling | Edamame | Eel | Onigiri(_, _) | MisoSoup | Sashimi | Tempura | Tofu => {
+                CardCategory::Appetizers
+            }
+            Chopsticks(_) | Menu(_) | SoySauce | Spoon(_) | SpecialOrder | TakeoutBox(_) | Tea
+            | Wasabi => CardCategory::Specials,
+            GreenTeaIceCream | Fruit(_, _, _) | Pudding => CardCategory::Desserts,
+        }
+    }
+
     pub fn is_dessert(self) -> bool {
+        self.category() == CardCategory::Desserts
+    }
+
+    // All Party variants of the menu item the card belongs to.
+    pub fn get_variants(self) -> Vec<Card> {
+        match self {
+            Nigiri(_) => vec![Nigiri(1), Nigiri(2), Nigiri(3)],
+            Maki(_) => vec![Maki(1), Maki(2), Maki(3)],
+            Uramaki(_) => vec![Uramaki(3), Uramaki(4), Uramaki(5)],
+            Onigiri(_, _) => vec![
+                Onigiri(false, false),
+                Onigiri(false, true),
+                Onigiri(true, false),
+                Onigiri(true, true),
+            ],
+            Chopsticks(_) => vec![Chopsticks(1), Chopsticks(2), Chopsticks(3)],
+            Spoon(_) => vec![Spoon(4), Spoon(5), Spoon(6)],
+            Menu(_) => vec![Menu(7), Menu(8), Menu(9)],
+            TakeoutBox(_) => vec![TakeoutBox(10), TakeoutBox(11), TakeoutBox(12)],
+            Fruit(_, _, _) => FRUIT_CARDS.to_vec(),
+            _ => vec![self],
+        }
+    }
+
+    // Count of the card in the original Sushi Go deck.
+    pub fn get_original_count(self) -> usize {
         match self {
-            GreenTeaIceCream | Fruit(_, _, _) | Pudding => true,
-            _ => false,
+            Tempura | Sashimi | Dumpling => 14,
+            Maki(1) => 6,
+            Maki(2) => 12,
+            Maki(3) => 8,
+            Nigiri(1) | Nigiri(3) => 5,
+            Nigiri(2) => 10,
+            Pudding => 10,
+            Wasabi => 6,
+            Chopsticks(0) => 4,
+            _ => {
+                panic!("Unknown original card: {:?}!", self);
+            }
         }
     }
 
+    // Special actions are resolved in order of their ranks (lowest first).
+    pub fn get_special_rank(self) -> Option<usize> {
+        match self {
+            Chopsticks(rank) | Spoon(rank) | Menu(rank) | TakeoutBox(rank) => Some(rank),
+            _ => None,
+        }
+    }
+
+    // Special order can copy any played card except special ones and flipped cards.
+    pub fn can_be_copied(self) -> bool {
+        !matches!(
+            self,
+            Chopsticks(_) | Menu(_) | Spoon(_) | SpecialOrder | TakeoutBox(_)
+        )
+    }
+
     pub fn get_color(self) -> CardColor {
         use CardColor::*;
         match self {
@@ -102,21 +260,9 @@ impl Card {
         }
     }
 
-    pub fn get_count(self, players: usize, round: usize) -> usize {
-        let dessert_cards = match (players, round) {
-            (2..=5, 1) => 5,
-            (2..=5, 2) => 3,
-            (2..=5, 3) => 2,
-            (6..=8, 1) => 7,
-            (6..=8, 2) => 5,
-            (6..=8, 3) => 3,
-            _ => {
-                panic!(
-                    "Invalid players count ({:?}) or round ({:?})!",
-                    players, round
-                );
-            }
-        };
+    // Note: for desserts this is count in the whole dessert pile,
+    // cards for each round are drawn from it (see get_dessert_count).
+    pub fn get_count(self) -> usize {
         match self {
             Nigiri(1) => 4,
             Nigiri(2) => 5,
@@ -130,16 +276,9 @@ impl Card {
             Onigiri(_, _) => 2, // 2 card per each combination
             Chopsticks(_) | Menu(_) | Spoon(_) | TakeoutBox(_) => 1, // 1 card per rank
             SoySauce | SpecialOrder | Tea | Wasabi => 3,
-            GreenTeaIceCream | Pudding => dessert_cards,
-
-            // there are 3 different fruits, args are counts of each fruit
-            // cards:
-            //   2 cards of double x each fruit (3) = 6 cards
-            //      2x (2, 0, 0), 2x (0, 2, 0), 2x (0, 0, 2)
-            //   3 cards of each combination of 2 fruits = 9 cards
-            //      3x (1, 1, 0), 3x (1, 0, 1), 3x (0, 1, 1)
-            // 15 cards in total
-            Fruit(_, _, _) => 0, // FIXME
+            GreenTeaIceCream | Pudding => 15,
+            Fruit(2, 0, 0) | Fruit(0, 2, 0) | Fruit(0, 0, 2) => 2,
+            Fruit(1, 1, 0) | Fruit(1, 0, 1) | Fruit(0, 1, 1) => 3,
 
             _ => {
                 panic!("Unknown card: {:?}!", self);
@@ -148,6 +287,43 @@ impl Card {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Ruleset {
+    Party,
+    Original, // original Sushi Go: single 108 cards deck, no menus
+}
+
+// Cards of the original Sushi Go (chopsticks have no ranks there).
+pub const ORIGINAL_CARDS: [Card; 12] = [
+    Nigiri(1),
+    Nigiri(2),
+    Nigiri(3),
+    Maki(1),
+    Maki(2),
+    Maki(3),
+    Tempura,
+    Sashimi,
+    Dumpling,
+    Wasabi,
+    Chopsticks(0),
+    Pudding,
+];
+
+impl Ruleset {
+    pub fn get_cards_per_player(self, players: usize) -> usize {
+        match (self, players) {
+            (Ruleset::Party, _) => get_cards_per_player(players),
+            (Ruleset::Original, 2) => 10,
+            (Ruleset::Original, 3) => 9,
+            (Ruleset::Original, 4) => 8,
+            (Ruleset::Original, 5) => 7,
+            (Ruleset::Original, _) => {
+                panic!("Invalid players count: {:?}!", players);
+            }
+        }
+    }
+}
+
 pub fn get_cards_per_player(players: usize) -> usize {
     match players {
         2..=3 => 10,
@@ -162,31 +338,73 @@ pub fn get_cards_per_player(players: usize) -> usize {
 
 pub type Menu = HashSet<Card>;
 
+// Each wasabi holds the first nigiri played after it, None for empty wasabi.
+pub fn get_wasabi_slots(played_cards: &[Card]) -> Vec<Option<Card>> {
+    let mut slots: Vec<Option<Card>> = Vec::new();
+    for card in played_cards {
+        match card {
+            Wasabi => slots.push(None),
+            Nigiri(_) => {
+                if let Some(slot) = slots.iter_mut().find(|slot| slot.is_none()) {
+                    *slot = Some(*card);
+                }
+            }
+            _ => {}
+        }
+    }
+    slots
+}
+
 pub fn has_fruit(menu: &Menu) -> bool {
-    menu.iter().any(|c| match c {
-        Fruit(_, _, _) => true,
-        _ => false,
-    })
+    menu.iter().any(|c| matches!(c, Fruit(_, _, _)))
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct CardSet {
-    set: HashMap<Card, usize>,
+    set: BTreeMap<Card, usize>, // ordered so random draws are reproducible
     count: usize,
 }
 
 impl CardSet {
     pub fn new() -> Self {
         CardSet {
-            set: HashMap::new(),
+            set: BTreeMap::new(),
             count: 0,
         }
     }
 
-    pub fn from_menu(menu: &Menu, players: usize, round: usize) -> Self {
+    // All non-dessert cards of the menu.
+    pub fn from_menu(menu: &Menu) -> Self {
         let mut set = CardSet::new();
-        for card in menu {
-            set.add_cards(*card, card.get_count(players, round));
+        for card in menu.iter().filter(|c| !c.is_dessert()) {
+            set.add_cards(*card, card.get_count());
+        }
+        set
+    }
+
+    // Whole deck of the original Sushi Go.
+    pub fn original_deck() -> Self {
+        let mut set = CardSet::new();
+        for card in ORIGINAL_CARDS.iter() {
+            set.add_cards(*card, card.get_original_count());
+        }
+        set
+    }
+
+    // Dessert pile of the menu, dessert cards for each round are drawn from it.
+    pub fn desserts_from_menu(menu: &Menu) -> Self {
+        let mut set = CardSet::new();
+        for card in menu
+            .iter()
+            .filter(|c| c.is_dessert() && !matches!(c, Fruit(_, _, _)))
+        {
+            set.add_cards(*card, card.get_count());
+        }
+        if has_fruit(menu) {
+            // any fruit on the menu means the whole fruit deck
+            for card in FRUIT_CARDS.iter() {
+                set.add_cards(*card, card.get_count());
+            }
         }
         set
     }
@@ -200,7 +418,11 @@ impl CardSet {
         self.count
     }
 
-    pub fn iter(&self) -> std::collections::hash_map::Iter<Card, usize> {
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn iter(&self) -> std::collections::btree_map::Iter<'_, Card, usize> {
         self.set.iter()
     }
 
@@ -221,16 +443,34 @@ impl CardSet {
         self.add_cards(card, 1)
     }
 
-    pub fn random_card(&self) -> Option<Card> {
+    pub fn add_set(&mut self, other: &CardSet) -> &mut Self {
+        for (card, count) in other.iter() {
+            self.add_cards(*card, *count);
+        }
+        self
+    }
+
+    pub fn random_card<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Card> {
         let total_count: usize = self.set.values().sum();
-        let mut ordinal = rand::thread_rng().gen::<usize>() % total_count;
+        let mut ordinal = rng.gen::<usize>() % total_count;
         for (card, count) in self.set.iter() {
             if *count > ordinal {
                 return Some(*card);
             }
             ordinal -= count;
         }
-        return None;
+        None
+    }
+
+    pub fn draw_random_cards<R: Rng + ?Sized>(&mut self, count: usize, rng: &mut R) -> CardSet {
+        let mut drawn = CardSet::new();
+        for _ in 0..count.min(self.len()) {
+            // unwrap -> there are still cards in set
+            let card = self.random_card(rng).unwrap();
+            self.remove_card(card);
+            drawn.add_card(card);
+        }
+        drawn
     }
 
     pub fn remove_card(&mut self, card: Card) {
@@ -244,6 +484,7 @@ impl CardSet {
         self.count -= 1;
     }
 
+    #[allow(clippy::manual_repeat_n)]
     pub fn flatten(&self) -> CardVec {
         self.iter()
             .flat_map(|(card, count)| repeat(*card).take(*count))
@@ -252,3 +493,65 @@ impl CardSet {
 }
 
 pub type CardVec = Vec<Card>;
+
+#[test]
+fn test_categories() {
+    let mut cards = 0;
+    for category in CardCategory::ALL.iter() {
+        for card in category.get_cards() {
+            assert_eq!(card.category(), *category);
+            assert!(card.get_count() > 0);
+            cards += 1;
+        }
+    }
+    assert_eq!(cards, 45);
+    assert_eq!(Spoon(5).get_variants(), vec![Spoon(4), Spoon(5), Spoon(6)]);
+}
+
+#[test]
+fn test_fruit_deck() {
+    let menu = [Tempura, Fruit(1, 1, 0)].iter().cloned().collect::<Menu>();
+    assert_eq!(CardSet::from_menu(&menu).len(), 8);
+
+    let desserts = CardSet::desserts_from_menu(&menu);
+    assert_eq!(desserts.len(), 15);
+    for card in FRUIT_CARDS.iter() {
+        assert!(desserts.contains_card(*card));
+    }
+}
+
+#[test]
+fn test_dessert_pile() {
+    use rand::SeedableRng;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let menu = [Tempura, Pudding].iter().cloned().collect::<Menu>();
+    let mut desserts = CardSet::desserts_from_menu(&menu);
+    for (round, count) in [(1, 7), (2, 5), (3, 3)].iter() {
+        let drawn = desserts.draw_random_cards(get_dessert_count(6, *round), &mut rng);
+        assert_eq!(drawn.len(), *count);
+    }
+    assert!(desserts.is_empty());
+
+    // the same seed draws the same cards
+    let menu = [Tempura, Fruit(2, 0, 0)].iter().cloned().collect::<Menu>();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let drawn = CardSet::desserts_from_menu(&menu)
+        .draw_random_cards(7, &mut rng)
+        .flatten();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    assert_eq!(
+        CardSet::desserts_from_menu(&menu)
+            .draw_random_cards(7, &mut rng)
+            .flatten(),
+        drawn
+    );
+}
+
+#[test]
+fn test_original_deck() {
+    assert_eq!(CardSet::original_deck().len(), 108);
+    for players in 2..=5 {
+        let cards_per_player = Ruleset::Original.get_cards_per_player(players);
+        assert!(players * cards_per_player * 3 <= 108);
+    }
+}
@@ -0,0 +1,342 @@
+use crate::cards::{Card, CardSet, CardVec, Menu, Ruleset};
+use crate::players::{simulate_game, GameContext, Player, SpoonRequest, Table};
+use crate::points::ScoreBreakdown;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+
+// Everything that happens in a game, in order. Rounds start with a deal,
+// turns with a play of all players (dummy included).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "event")]
+pub enum GameEvent {
+    Deal {
+        round: usize,
+        hands: Vec<CardVec>,
+    },
+    Play {
+        turn: usize,
+        cards: CardVec, // card revealed by each player
+    },
+    Chopsticks {
+        player_idx: usize,
+        card: Option<Card>,
+    },
+    Spoon {
+        player_idx: usize,
+        request: Option<SpoonRequest>,
+        giver_idx: Option<usize>,
+        card: Option<Card>,
+    },
+    Menu {
+        player_idx: usize,
+        options: CardVec,
+        card: Option<Card>,
+    },
+    TakeoutBox {
+        player_idx: usize,
+        flipped: Vec<usize>,
+    },
+    SpecialOrder {
+        player_idx: usize,
+        card: Option<Card>,
+    },
+    UramakiScore {
+        player_idx: usize,
+        points: isize,
+    },
+    Pass {
+        turn: usize,
+        hands: Vec<CardVec>, // hand each player gets for the next turn
+    },
+    RoundScore {
+        round: usize,
+        points: Vec<ScoreBreakdown>,
+    },
+    DessertScore {
+        points: Vec<ScoreBreakdown>, // dessert categories only
+    },
+}
+
+// Collects events only when logging is enabled, so plain simulations don't pay for it.
+#[derive(Debug, Default)]
+pub struct EventLog(Option<Vec<GameEvent>>);
+
+impl EventLog {
+    pub fn enabled() -> Self {
+        EventLog(Some(Vec::new()))
+    }
+
+    pub fn push<F: FnOnce() -> GameEvent>(&mut self, event: F) {
+        if let Some(events) = self.0.as_mut() {
+            events.push(event());
+        }
+    }
+
+    pub fn into_events(self) -> Vec<GameEvent> {
+        self.0.unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GameLog {
+    pub ruleset: Ruleset,
+    pub menu: CardVec, // sorted
+    pub seed: u64,
+    pub players_count: usize,
+    pub with_dummy: bool,
+    pub events: Vec<GameEvent>,
+    pub scores: Vec<isize>,
+}
+
+impl GameLog {
+    pub fn get_menu(&self) -> Menu {
+        self.menu.iter().cloned().collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        // unwrap -> the log contains only serializable values
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReplayError {
+    EventsMismatch {
+        position: usize,
+    },
+    ScoresMismatch {
+        logged: Vec<isize>,
+        replayed: Vec<isize>,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::EventsMismatch { position } => {
+                write!(f, "Replayed event #{} differs from the log", position)
+            }
+            ReplayError::ScoresMismatch { logged, replayed } => {
+                write!(
+                    f,
+                    "Replayed scores {:?} differ from logged {:?}",
+                    replayed, logged
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+// Repeats decisions of a player recorded in the log.
+#[derive(Debug, Default)]
+struct ReplayPlayer {
+    plays: VecDeque<Card>,
+    chopsticks: VecDeque<Option<Card>>,
+    spoons: VecDeque<Option<SpoonRequest>>,
+    menus: VecDeque<Card>,
+    takeout_boxes: VecDeque<Vec<usize>>,
+    special_orders: VecDeque<Option<Card>>,
+}
+
+impl Player for ReplayPlayer {
+    fn play(
+        &mut self,
+        _hand: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Card {
+        self.plays.pop_front().expect("No more plays in the log!")
+    }
+
+    fn use_chopsticks(
+        &mut self,
+        _hand: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Option<Card> {
+        self.chopsticks
+            .pop_front()
+            .expect("No more chopsticks in the log!")
+    }
+
+    fn use_spoon(
+        &mut self,
+        _hand: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Option<SpoonRequest> {
+        self.spoons.pop_front().expect("No more spoons in the log!")
+    }
+
+    fn choose_from_menu(
+        &mut self,
+        _options: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Card {
+        self.menus.pop_front().expect("No more menus in the log!")
+    }
+
+    fn use_takeout_box(
+        &mut self,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Vec<usize> {
+        self.takeout_boxes
+            .pop_front()
+            .expect("No more takeout boxes in the log!")
+    }
+
+    fn choose_special_order_copy(
+        &mut self,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Option<Card> {
+        self.special_orders
+            .pop_front()
+            .expect("No more special orders in the log!")
+    }
+}
+
+// Re-runs the logged game with the logged decisions and checks that it
+// goes the same way. Returns the replayed scores.
+pub fn replay(log: &GameLog) -> Result<Vec<isize>, ReplayError> {
+    let seats_count = log.players_count + if log.with_dummy { 1 } else { 0 };
+    let mut players: Vec<ReplayPlayer> =
+        (0..seats_count).map(|_| ReplayPlayer::default()).collect();
+    for event in log.events.iter() {
+        match event {
+            GameEvent::Play { cards, .. } => {
+                for (player, card) in players.iter_mut().zip(cards.iter()) {
+                    player.plays.push_back(*card);
+                }
+            }
+            GameEvent::Chopsticks { player_idx, card } => {
+                players[*player_idx].chopsticks.push_back(*card)
+            }
+            GameEvent::Spoon {
+                player_idx,
+                request,
+                ..
+            } => players[*player_idx].spoons.push_back(*request),
+            GameEvent::Menu {
+                player_idx,
+                card: Some(card),
+                ..
+            } => players[*player_idx].menus.push_back(*card),
+            GameEvent::TakeoutBox {
+                player_idx,
+                flipped,
+            } => players[*player_idx]
+                .takeout_boxes
+                .push_back(flipped.clone()),
+            GameEvent::SpecialOrder { player_idx, card } => {
+                players[*player_idx].special_orders.push_back(*card)
+            }
+            _ => {}
+        }
+    }
+
+    let mut table = Table(
+        players
+            .into_iter()
+            .map(|player| Box::new(player) as Box<dyn Player + Send>)
+            .collect(),
+    );
+    let mut rng = StdRng::seed_from_u64(log.seed);
+    let mut context = GameContext::new(&mut rng, EventLog::enabled());
+    let mut scores = simulate_game(
+        log.ruleset,
+        &log.get_menu(),
+        &mut table,
+        log.with_dummy,
+        &mut context,
+    );
+    if log.with_dummy {
+        scores.pop();
+    }
+
+    let events = context.log.into_events();
+    if let Some(position) =
+        (0..events.len().max(log.events.len())).find(|idx| events.get(*idx) != log.events.get(*idx))
+    {
+        return Err(ReplayError::EventsMismatch { position });
+    }
+    if scores != log.scores {
+        return Err(ReplayError::ScoresMismatch {
+            logged: log.scores.clone(),
+            replayed: scores,
+        });
+    }
+    Ok(scores)
+}
+
+#[test]
+fn test_json_format() {
+    use crate::cards::Card::*;
+    let event = GameEvent::Play {
+        turn: 0,
+        cards: vec![Nigiri(3), Temaki, Fruit(1, 1, 0)],
+    };
+    let json = serde_json::to_string(&event).unwrap();
+    assert_eq!(
+        json,
+        r#"{"event":"Play","turn":0,"cards":[{"Nigiri":3},"Temaki",{"Fruit":[1,1,0]}]}"#
+    );
+    assert_eq!(serde_json::from_str::<GameEvent>(&json).unwrap(), event);
+}
+
+#[test]
+fn test_replay() {
+    use crate::menus::get_official_menu;
+    use crate::players::{simulate_with_log, PreferedCardsPlayer, RandomPlayer};
+    let p = RandomPlayer::default();
+    for seed in 0..10 {
+        let menu = get_official_menu("Master Menu").unwrap().get_menu();
+        let mut players = (p, PreferedCardsPlayer::new_wasabi_best_nigiri(), p, p);
+        let log = simulate_with_log(Ruleset::Party, &menu, &mut players, seed);
+        let parsed = GameLog::from_json(&log.to_json()).unwrap();
+        assert_eq!(parsed, log);
+        assert_eq!(replay(&parsed), Ok(log.scores.clone()));
+
+        // 2 players with the dummy
+        let menu = get_official_menu("Dinner for Two").unwrap().get_menu();
+        let log = simulate_with_log(Ruleset::Party, &menu, &mut (p, p), seed);
+        assert_eq!(log.scores.len(), 2);
+        assert_eq!(replay(&log), Ok(log.scores.clone()));
+    }
+}
+
+#[test]
+fn test_replay_mismatch() {
+    use crate::players::{simulate_with_log, RandomPlayer};
+    let p = RandomPlayer::default();
+    let menu = crate::cards::ORIGINAL_CARDS
+        .iter()
+        .cloned()
+        .collect::<Menu>();
+    let mut log = simulate_with_log(Ruleset::Original, &menu, &mut (p, p, p), 7);
+    log.scores[0] += 1;
+    assert_eq!(
+        replay(&log),
+        Err(ReplayError::ScoresMismatch {
+            logged: log.scores.clone(),
+            replayed: vec![log.scores[0] - 1, log.scores[1], log.scores[2]],
+        })
+    );
+}
@@ -0,0 +1,5 @@
+pub mod cards;
+pub mod events;
+pub mod menus;
+pub mod players;
+pub mod points;
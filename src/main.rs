@@ -1,39 +1,25 @@
-mod cards;
-mod players;
-mod points;
-
-use crate::cards::Card;
-use crate::cards::Card::*;
-use crate::cards::Menu;
-use crate::players::{simulate, Player, Players, RandomPlayer, PreferedCardsPlayer};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sim_sushi_go::cards::Menu;
+use sim_sushi_go::menus::{get_official_menu, MenuError};
+use sim_sushi_go::players::{
+    try_simulate, Player, Players, PreferedCardsPlayer, RandomPlayer, Table,
+};
 use std::collections::HashSet;
 
-const MENU_MY_FIRST_MEAL: [Card; 12] = [
-    Nigiri(1),
-    Nigiri(2),
-    Nigiri(3),
-    Maki(1),
-    Maki(2),
-    Maki(3),
-    Tempura,
-    Sashimi,
-    MisoSoup,
-    Wasabi,
-    Tea,
-    GreenTeaIceCream,
-];
-
-fn run_multiple_simulations<P>(count: usize, menu: Menu, players: P)
+fn run_multiple_simulations<P>(count: usize, menu: Menu, players: P) -> Result<(), MenuError>
 where
     P: Players,
 {
     let mut players = players;
-    let mut final_scores = vec![0; P::COUNT];
-    let mut positions = vec![vec![0; P::COUNT]; P::COUNT];
+    let players_count = players.count();
+    let mut final_scores = vec![0; players_count];
+    let mut positions = vec![vec![0; players_count]; players_count];
 
-    for _ in 0..count {
-        // simulate
-        let points = simulate(&menu, &mut players);
+    for game in 0..count {
+        // simulate (each game is seeded with its number so it can be replayed)
+        let mut rng = StdRng::seed_from_u64(game as u64);
+        let points = try_simulate(&menu, &mut players, &mut rng)?;
 
         // update positions
         let mut ranks = points.iter().cloned().collect::<HashSet<isize>>().iter().cloned().collect::<Vec<isize>>();
@@ -70,53 +56,55 @@ where
         println!("{}", s);
     });
     println!();
+    Ok(())
 }
 
-fn run_multiple_combinations<A, B, C, D>(count: usize, menu: Menu, a: A, b: B, c: C, d: D)
-where
-    A: Player + Clone + std::fmt::Debug,
-    B: Player + Clone + std::fmt::Debug,
-    C: Player + Clone + std::fmt::Debug,
-    D: Player + Clone + std::fmt::Debug,
-{
-    run_multiple_simulations(
-        count, menu.clone(),
-        (a.clone(), b.clone(), c.clone(), d.clone()),
-    );
-
-    run_multiple_simulations(
-        count, menu.clone(),
-        (a.clone(), b.clone(), d.clone(), c.clone()),
-    );
+type PlayerFactory = fn() -> Box<dyn Player + Send>;
 
-    run_multiple_simulations(
-        count, menu.clone(),
-        (a.clone(), c.clone(), b.clone(), d.clone()),
-    );
-
-    run_multiple_simulations(
-        count, menu.clone(),
-        (a.clone(), c.clone(), d.clone(), b.clone()),
-    );
-
-    run_multiple_simulations(
-        count, menu.clone(),
-        (a.clone(), d.clone(), b.clone(), c.clone()),
-    );
+fn permutations(items: &[usize]) -> Vec<Vec<usize>> {
+    if items.is_empty() {
+        return vec![vec![]];
+    }
+    let mut result = Vec::new();
+    for (pos, item) in items.iter().enumerate() {
+        let mut rest = items.to_vec();
+        rest.remove(pos);
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, *item);
+            result.push(permutation);
+        }
+    }
+    result
+}
 
-    run_multiple_simulations(
-        count, menu.clone(),
-        (a.clone(), d.clone(), c.clone(), b.clone()),
-    );
+// Runs simulations for the first player with every ordering of the others.
+fn run_multiple_combinations(
+    count: usize,
+    menu: Menu,
+    first: PlayerFactory,
+    others: &[PlayerFactory],
+) -> Result<(), MenuError> {
+    for order in permutations(&(0..others.len()).collect::<Vec<usize>>()) {
+        let mut players = vec![first()];
+        players.extend(order.iter().map(|idx| others[*idx]()));
+        run_multiple_simulations(count, menu.clone(), Table(players))?;
+    }
+    Ok(())
 }
 
 fn main() {
-    run_multiple_combinations(
+    let result = run_multiple_combinations(
         1000,
-        MENU_MY_FIRST_MEAL.iter().cloned().collect::<Menu>(),
-        RandomPlayer::default(),
-        PreferedCardsPlayer::new_best_nigiri(),
-        PreferedCardsPlayer::new_wasabi_best_nigiri(),
-        PreferedCardsPlayer::new_nigiri_master(),
+        get_official_menu("My First Meal").unwrap().get_menu(),
+        || Box::new(RandomPlayer::default()),
+        &[
+            || Box::new(PreferedCardsPlayer::new_best_nigiri()),
+            || Box::new(PreferedCardsPlayer::new_wasabi_best_nigiri()),
+            || Box::new(PreferedCardsPlayer::new_nigiri_master()),
+        ],
     );
+    if let Err(error) = result {
+        eprintln!("Invalid menu: {}", error);
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,352 @@
+use crate::cards::Card::*;
+use crate::cards::{Card, CardCategory, CardVec, Menu};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::fmt;
+use std::mem::discriminant;
+use std::ops::RangeInclusive;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MenuError {
+    InvalidPlayersCount(usize),
+    UnknownCard(Card),
+    IncompleteItem(Card), // only some variants of the item are on the menu
+    WrongItemsCount {
+        category: CardCategory,
+        expected: usize,
+        found: usize,
+    },
+    UnsupportedPlayersCount {
+        card: Card,
+        players: usize,
+    },
+}
+
+impl fmt::Display for MenuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MenuError::InvalidPlayersCount(players) => {
+                write!(f, "Invalid players count: {:?}", players)
+            }
+            MenuError::UnknownCard(card) => write!(f, "Unknown card: {:?}", card),
+            MenuError::IncompleteItem(card) => {
+                write!(f, "Not all variants of {:?} are on the menu", card)
+            }
+            MenuError::WrongItemsCount {
+                category,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Expected {} {:?} on the menu, found {}",
+                expected, category, found
+            ),
+            MenuError::UnsupportedPlayersCount { card, players } => {
+                write!(f, "{:?} can't be used with {} players", card, players)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MenuError {}
+
+// Player counts the card can be used with.
+fn get_supported_players(card: Card) -> RangeInclusive<usize> {
+    match card {
+        Edamame | Spoon(_) => 3..=8,
+        Menu(_) => 2..=7,
+        _ => 2..=8,
+    }
+}
+
+// Checks the menu against the Sushi Go Party menu building rules.
+pub fn validate_menu(menu: &Menu, players: usize) -> Result<(), MenuError> {
+    if !(2..=8).contains(&players) {
+        return Err(MenuError::InvalidPlayersCount(players));
+    }
+
+    // cards are checked in a fixed order, so a menu with several problems
+    // always reports the same one
+    let mut cards = menu.iter().cloned().collect::<CardVec>();
+    cards.sort();
+    let mut items = HashMap::new();
+    for card in cards.iter() {
+        let variants = card.get_variants();
+        if !variants.contains(card) {
+            return Err(MenuError::UnknownCard(*card));
+        }
+        // items are reported by their first variant
+        if !variants.iter().all(|variant| menu.contains(variant)) {
+            return Err(MenuError::IncompleteItem(variants[0]));
+        }
+        if !get_supported_players(*card).contains(&players) {
+            return Err(MenuError::UnsupportedPlayersCount {
+                card: variants[0],
+                players,
+            });
+        }
+        items.insert(discriminant(card), variants[0]);
+    }
+
+    for category in CardCategory::ALL.iter().cloned() {
+        let expected = category.get_menu_items_count();
+        let found = items
+            .values()
+            .filter(|card| card.category() == category)
+            .count();
+        if found != expected {
+            return Err(MenuError::WrongItemsCount {
+                category,
+                expected,
+                found,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// Picks a random legal menu for the players count, the same seed always gives the same menu.
+pub fn random_menu(players: usize, seed: u64) -> Result<Menu, MenuError> {
+    if !(2..=8).contains(&players) {
+        return Err(MenuError::InvalidPlayersCount(players));
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut menu = Menu::new();
+    for category in CardCategory::ALL.iter() {
+        let items = category
+            .get_items()
+            .into_iter()
+            .filter(|card| get_supported_players(*card).contains(&players))
+            .collect::<Vec<Card>>();
+        for card in items.choose_multiple(&mut rng, category.get_menu_items_count()) {
+            menu.extend(card.get_variants());
+        }
+    }
+
+    Ok(menu)
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OfficialMenu {
+    pub name: &'static str,
+    pub items: &'static [Card], // one card for each menu item
+    pub players: RangeInclusive<usize>,
+}
+
+impl OfficialMenu {
+    pub fn get_menu(&self) -> Menu {
+        self.items
+            .iter()
+            .flat_map(|card| card.get_variants())
+            .collect()
+    }
+
+    pub fn supports_players(&self, players: usize) -> bool {
+        self.players.contains(&players)
+    }
+}
+
+// Suggested menus from the Sushi Go Party rulebook.
+pub const OFFICIAL_MENUS: [OfficialMenu; 8] = [
+    OfficialMenu {
+        name: "My First Meal",
+        items: &[
+            Nigiri(1),
+            Maki(1),
+            Tempura,
+            Sashimi,
+            MisoSoup,
+            Wasabi,
+            Tea,
+            GreenTeaIceCream,
+        ],
+        players: 2..=8,
+    },
+    OfficialMenu {
+        name: "Sushi Go!",
+        items: &[
+            Nigiri(1),
+            Maki(1),
+            Tempura,
+            Sashimi,
+            Dumpling,
+            Chopsticks(1),
+            Wasabi,
+            Pudding,
+        ],
+        players: 2..=8,
+    },
+    OfficialMenu {
+        name: "Party Sampler",
+        items: &[
+            Nigiri(1),
+            Temaki,
+            Tempura,
+            Dumpling,
+            Tofu,
+            Wasabi,
+            Menu(7),
+            GreenTeaIceCream,
+        ],
+        players: 2..=7,
+    },
+    OfficialMenu {
+        name: "Master Menu",
+        items: &[
+            Nigiri(1),
+            Temaki,
+            Onigiri(false, false),
+            Tofu,
+            Sashimi,
+            Spoon(4),
+            TakeoutBox(10),
+            Fruit(1, 1, 0),
+        ],
+        players: 3..=8,
+    },
+    OfficialMenu {
+        name: "Points Platter",
+        items: &[
+            Nigiri(1),
+            Uramaki(3),
+            Onigiri(false, false),
+            Dumpling,
+            Edamame,
+            SpecialOrder,
+            Tea,
+            GreenTeaIceCream,
+        ],
+        players: 3..=8,
+    },
+    OfficialMenu {
+        name: "Cutthroat Combo",
+        items: &[
+            Nigiri(1),
+            Temaki,
+            Eel,
+            Tofu,
+            MisoSoup,
+            Spoon(4),
+            SoySauce,
+            Pudding,
+        ],
+        players: 3..=8,
+    },
+    OfficialMenu {
+        name: "Big Banquet",
+        items: &[
+            Nigiri(1),
+            Maki(1),
+            Tempura,
+            Dumpling,
+            Eel,
+            Spoon(4),
+            Chopsticks(1),
+            GreenTeaIceCream,
+        ],
+        players: 6..=8,
+    },
+    OfficialMenu {
+        name: "Dinner for Two",
+        items: &[
+            Nigiri(1),
+            Uramaki(3),
+            Onigiri(false, false),
+            Tofu,
+            MisoSoup,
+            Menu(7),
+            SpecialOrder,
+            Fruit(1, 1, 0),
+        ],
+        players: 2..=2,
+    },
+];
+
+pub fn get_official_menu(name: &str) -> Option<&'static OfficialMenu> {
+    OFFICIAL_MENUS.iter().find(|menu| menu.name == name)
+}
+
+#[test]
+fn test_official_menus() {
+    for official in OFFICIAL_MENUS.iter() {
+        for players in official.players.clone() {
+            assert_eq!(
+                validate_menu(&official.get_menu(), players),
+                Ok(()),
+                "{}",
+                official.name
+            );
+        }
+    }
+    assert_eq!(get_official_menu("Sushi Go!").unwrap().get_menu().len(), 14);
+    assert!(get_official_menu("Sushi Went").is_none());
+}
+
+#[test]
+fn test_validate_menu() {
+    let menu = get_official_menu("Party Sampler").unwrap().get_menu();
+    assert_eq!(
+        validate_menu(&menu, 8),
+        Err(MenuError::UnsupportedPlayersCount {
+            card: Menu(7),
+            players: 8
+        })
+    );
+    assert_eq!(
+        validate_menu(&menu, 9),
+        Err(MenuError::InvalidPlayersCount(9))
+    );
+
+    let mut incomplete = menu.clone();
+    incomplete.remove(&Nigiri(2));
+    assert_eq!(
+        validate_menu(&incomplete, 4),
+        Err(MenuError::IncompleteItem(Nigiri(1)))
+    );
+
+    let mut unknown = menu.clone();
+    unknown.insert(Nigiri(4));
+    assert_eq!(
+        validate_menu(&unknown, 4),
+        Err(MenuError::UnknownCard(Nigiri(4)))
+    );
+
+    // the same problem is reported whatever order the menu is iterated in
+    let mut broken = menu.clone();
+    broken.remove(&Menu(8));
+    broken.insert(Nigiri(4));
+    for _ in 0..20 {
+        let rebuilt = broken.iter().cloned().collect::<Menu>();
+        assert_eq!(
+            validate_menu(&rebuilt, 4),
+            Err(MenuError::UnknownCard(Nigiri(4)))
+        );
+    }
+
+    let mut too_many = menu.clone();
+    too_many.insert(Eel);
+    assert_eq!(
+        validate_menu(&too_many, 4),
+        Err(MenuError::WrongItemsCount {
+            category: CardCategory::Appetizers,
+            expected: 3,
+            found: 4
+        })
+    );
+}
+
+#[test]
+fn test_random_menu() {
+    for players in 2..=8 {
+        for seed in 0..50 {
+            let menu = random_menu(players, seed).unwrap();
+            assert_eq!(validate_menu(&menu, players), Ok(()));
+            assert_eq!(menu, random_menu(players, seed).unwrap());
+        }
+    }
+    assert_eq!(random_menu(1, 0), Err(MenuError::InvalidPlayersCount(1)));
+}
@@ -1,18 +1,104 @@
 use crate::cards;
 use crate::cards::Card::*;
-use crate::cards::{Card, CardSet, CardVec, Menu};
-use crate::points::PointCalculator;
+use crate::cards::{Card, CardSet, CardVec, Menu, Ruleset};
+use crate::events::{EventLog, GameEvent, GameLog};
+use crate::menus::{validate_menu, MenuError};
+use crate::points::{PointCalculator, ScoreBreakdown, ScoringEvent};
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
 use std::iter::repeat_with;
+use std::mem::discriminant;
 
-pub trait Player {
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum SpoonRequest {
+    Card(Card), // exactly this card
+    Kind(Card), // any card of the same kind (e.g. any nigiri for Nigiri(_))
+}
+
+impl SpoonRequest {
+    pub fn matches(self, card: Card) -> bool {
+        match self {
+            SpoonRequest::Card(requested) => requested == card,
+            SpoonRequest::Kind(requested) => discriminant(&requested) == discriminant(&card),
+        }
+    }
+}
+
+pub trait Player: std::fmt::Debug {
     fn play(
         &mut self,
         hand: &CardSet,
         player_idx: usize,
         played_cards: &[CardVec], /* TODO: add desserts played */
+        rng: &mut StdRng,
     ) -> Card;
-    // TODO: add support for playing special cards
+
+    // Called after all players revealed their cards if the player has chopsticks
+    // on the table. Returned card is played too and chopsticks go back to the hand.
+    fn use_chopsticks(
+        &mut self,
+        _hand: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Option<Card> {
+        None
+    }
+
+    // Called after all players revealed their cards if the player has a spoon
+    // on the table. Requested card is taken from the first other player (in turn
+    // order) that has it in hand, the spoon goes to that player's hand.
+    // If nobody has it the spoon is discarded.
+    fn use_spoon(
+        &mut self,
+        _hand: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Option<SpoonRequest> {
+        None
+    }
+
+    // Called when the player played a menu card. Options are cards drawn from
+    // the deck, chosen one is played and the others are shuffled back.
+    fn choose_from_menu(
+        &mut self,
+        options: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        rng: &mut StdRng,
+    ) -> Card {
+        options.random_card(rng).unwrap()
+    }
+
+    // Called when the player played a takeout box. Returns positions of already
+    // played cards (in played_cards[player_idx]) to flip face down for 2 points each.
+    fn use_takeout_box(
+        &mut self,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Vec<usize> {
+        Vec::new()
+    }
+
+    // Called when the player played a special order. Returns one of the already
+    // played cards to copy, if nothing is copied it counts only as a rainbow card.
+    fn choose_special_order_copy(
+        &mut self,
+        player_idx: usize,
+        played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Option<Card> {
+        played_cards[player_idx]
+            .iter()
+            .rev()
+            .cloned()
+            .find(|card| card.can_be_copied())
+    }
 }
 
 pub struct HandsView<'a> {
@@ -41,6 +127,24 @@ impl<'a> HandsView<'a> {
         HandsView::get_wrapped(self.hands, self.hands_shift + idx as isize)
     }
 
+    // Finds the first other player (in turn order) that has requested card in hand.
+    pub fn find_requested_card(
+        &self,
+        player_idx: usize,
+        request: SpoonRequest,
+    ) -> Option<(usize, Card)> {
+        (1..self.hands.len())
+            .map(|offset| (player_idx + offset) % self.hands.len())
+            .filter_map(|idx| {
+                self.get_hand(idx)
+                    .iter()
+                    .map(|(card, _)| *card)
+                    .find(|card| request.matches(*card))
+                    .map(|card| (idx, card))
+            })
+            .next()
+    }
+
     fn get_hand_mut(set: &'a mut [CardSet], hands_shift: isize, idx: usize) -> &'a mut CardSet {
         HandsView::get_wrapped_mut(set, hands_shift + idx as isize)
     }
@@ -65,94 +169,645 @@ fn test_get_wrapped() {
 }
 
 pub trait Players {
-    const COUNT: usize;
+    fn count(&self) -> usize;
 
-    fn play(&mut self, hands: &HandsView, played_cards: &[CardVec], output: &mut [Option<Card>]);
+    fn play(
+        &mut self,
+        hands: &HandsView,
+        played_cards: &[CardVec],
+        output: &mut [Option<Card>],
+        rng: &mut StdRng,
+    );
+    fn get_player_mut(&mut self, idx: usize) -> &mut dyn Player;
     fn iter_for_printing<F: Fn(usize, String)>(&self, f: F);
 }
 
-impl<A, B, C, D> Players for (A, B, C, D)
+macro_rules! impl_players_for_tuple {
+    ($count:expr; $($idx:tt: $player:ident),+) => {
+        impl<$($player),+> Players for ($($player,)+)
+        where
+            $($player: Player,)+
+        {
+            fn count(&self) -> usize {
+                $count
+            }
+
+            fn play(
+                &mut self,
+                hands: &HandsView,
+                played_cards: &[CardVec],
+                output: &mut [Option<Card>],
+                rng: &mut StdRng,
+            ) {
+                $(output[$idx] = Some(self.$idx.play(hands.get_hand($idx), $idx, played_cards, rng));)+
+            }
+
+            fn get_player_mut(&mut self, idx: usize) -> &mut dyn Player {
+                match idx {
+                    $($idx => &mut self.$idx,)+
+                    _ => {
+                        panic!("Invalid player index: {:?}!", idx);
+                    }
+                }
+            }
+
+            fn iter_for_printing<F: Fn(usize, String)>(&self, f: F) {
+                $(f($idx, format!("{:?}", self.$idx));)+
+            }
+        }
+    };
+}
+
+impl_players_for_tuple!(2; 0: P0, 1: P1);
+impl_players_for_tuple!(3; 0: P0, 1: P1, 2: P2);
+impl_players_for_tuple!(4; 0: P0, 1: P1, 2: P2, 3: P3);
+impl_players_for_tuple!(5; 0: P0, 1: P1, 2: P2, 3: P3, 4: P4);
+impl_players_for_tuple!(6; 0: P0, 1: P1, 2: P2, 3: P3, 4: P4, 5: P5);
+impl_players_for_tuple!(7; 0: P0, 1: P1, 2: P2, 3: P3, 4: P4, 5: P5, 6: P6);
+impl_players_for_tuple!(8; 0: P0, 1: P1, 2: P2, 3: P3, 4: P4, 5: P5, 6: P6, 7: P7);
+
+// Lineup of players built at runtime.
+#[derive(Debug, Default)]
+pub struct Table(pub Vec<Box<dyn Player + Send>>);
+
+impl Players for Table {
+    fn count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn play(
+        &mut self,
+        hands: &HandsView,
+        played_cards: &[CardVec],
+        output: &mut [Option<Card>],
+        rng: &mut StdRng,
+    ) {
+        for (idx, player) in self.0.iter_mut().enumerate() {
+            output[idx] = Some(player.play(hands.get_hand(idx), idx, played_cards, rng));
+        }
+    }
+
+    fn get_player_mut(&mut self, idx: usize) -> &mut dyn Player {
+        self.0[idx].as_mut()
+    }
+
+    fn iter_for_printing<F: Fn(usize, String)>(&self, f: F) {
+        for (idx, player) in self.0.iter().enumerate() {
+            f(idx, format!("{:?}", player));
+        }
+    }
+}
+
+// Dummy of the 2 players variant: it flips the top card of its hand every turn.
+#[derive(Clone, Copy, Debug, Default)]
+struct DummyPlayer {}
+impl Player for DummyPlayer {
+    fn play(
+        &mut self,
+        hand: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        rng: &mut StdRng,
+    ) -> Card {
+        hand.random_card(rng).unwrap()
+    }
+
+    fn choose_special_order_copy(
+        &mut self,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Option<Card> {
+        None
+    }
+}
+
+// Players with a dummy sitting as the last one.
+struct WithDummy<'a, P> {
+    players: &'a mut P,
+    dummy: DummyPlayer,
+}
+
+impl<'a, P> Players for WithDummy<'a, P>
 where
-    A: Player + std::fmt::Debug,
-    B: Player + std::fmt::Debug,
-    C: Player + std::fmt::Debug,
-    D: Player + std::fmt::Debug,
+    P: Players,
 {
-    const COUNT: usize = 4;
+    fn count(&self) -> usize {
+        self.players.count() + 1
+    }
 
-    fn play(&mut self, hands: &HandsView, played_cards: &[CardVec], output: &mut [Option<Card>]) {
-        output[0] = Some(self.0.play(hands.get_hand(0), 0, played_cards));
-        output[1] = Some(self.1.play(hands.get_hand(1), 1, played_cards));
-        output[2] = Some(self.2.play(hands.get_hand(2), 2, played_cards));
-        output[3] = Some(self.3.play(hands.get_hand(3), 3, played_cards));
+    fn play(
+        &mut self,
+        hands: &HandsView,
+        played_cards: &[CardVec],
+        output: &mut [Option<Card>],
+        rng: &mut StdRng,
+    ) {
+        let dummy_idx = self.players.count();
+        self.players.play(hands, played_cards, output, rng);
+        output[dummy_idx] =
+            Some(
+                self.dummy
+                    .play(hands.get_hand(dummy_idx), dummy_idx, played_cards, rng),
+            );
+    }
+
+    fn get_player_mut(&mut self, idx: usize) -> &mut dyn Player {
+        if idx == self.players.count() {
+            &mut self.dummy
+        } else {
+            self.players.get_player_mut(idx)
+        }
     }
 
     fn iter_for_printing<F: Fn(usize, String)>(&self, f: F) {
-        f(0, format!("{:?}", self.0));
-        f(1, format!("{:?}", self.1));
-        f(2, format!("{:?}", self.2));
-        f(3, format!("{:?}", self.3));
+        self.players.iter_for_printing(&f);
+        f(self.players.count(), format!("{:?}", self.dummy));
     }
 }
 
 const ROUNDS_COUNT: usize = 3;
 const MAX_PLAYERS: usize = 9;
+const MENU_OPTIONS: usize = 4;
+
+// Randomness and event log of a simulated game. Shuffling and drawing use their
+// own rng split off at the start, so deals don't depend on players' randomness.
+pub(crate) struct GameContext<'a> {
+    rng: &'a mut StdRng,
+    deck_rng: StdRng,
+    pub(crate) log: EventLog,
+}
+
+impl<'a> GameContext<'a> {
+    pub(crate) fn new(rng: &'a mut StdRng, log: EventLog) -> Self {
+        // unwrap -> seeding from another StdRng can't fail
+        let deck_rng = StdRng::from_rng(&mut *rng).unwrap();
+        GameContext { rng, deck_rng, log }
+    }
+}
+
+// Plays a single turn: players choose their cards, special actions are resolved
+// and the cards are put on the table. Returns cards played by each player.
+fn play_turn<P>(
+    players: &mut P,
+    hands: &mut [CardSet],
+    deck: &mut CardVec,
+    turn: usize,
+    played_cards: &mut [CardVec],
+    points: &mut PointCalculator,
+    context: &mut GameContext,
+) -> Vec<CardVec>
+where
+    P: Players,
+{
+    let hands_view = HandsView::new(hands, turn as isize);
+    let mut played_now = [None; MAX_PLAYERS];
+    players.play(&hands_view, played_cards, &mut played_now, context.rng);
+    let mut turn_cards: Vec<CardVec> = Vec::with_capacity(played_cards.len());
+    for (idx, played_card_option) in played_now.iter().take(played_cards.len()).enumerate() {
+        // unwrap played card, if None -> panic
+        let played_card = played_card_option.unwrap();
+        HandsView::get_hand_mut(hands, turn as isize, idx).remove_card(played_card); // FIXME: get liftimes in order
+        turn_cards.push(vec![played_card]);
+    }
+    context.log.push(|| GameEvent::Play {
+        turn,
+        cards: turn_cards.iter().flatten().cloned().collect(),
+    });
+
+    // resolve special actions in order of their ranks
+    // (chopsticks and spoons already on the table, menus and takeout boxes played now)
+    let mut specials = played_cards
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, cards)| {
+            cards
+                .iter()
+                .filter(|card| matches!(card, Chopsticks(_) | Spoon(_)))
+                .map(move |card| (idx, *card))
+        })
+        .chain(turn_cards.iter().enumerate().flat_map(|(idx, cards)| {
+            cards
+                .iter()
+                .filter(|card| matches!(card, Menu(_) | TakeoutBox(_)))
+                .map(move |card| (idx, *card))
+        }))
+        .filter_map(|(idx, card)| card.get_special_rank().map(|rank| (rank, idx, card)))
+        .collect::<Vec<(usize, usize, Card)>>();
+    // (sorted from the last one, so the next one to resolve can be popped)
+    specials.sort_by_key(|(rank, idx, _)| Reverse((*rank, *idx)));
+    while let Some((_, idx, special)) = specials.pop() {
+        let hand = HandsView::get_hand_mut(hands, turn as isize, idx);
+        match special {
+            Chopsticks(_) => {
+                let chosen = players.get_player_mut(idx).use_chopsticks(
+                    hand,
+                    idx,
+                    played_cards,
+                    context.rng,
+                );
+                context.log.push(|| GameEvent::Chopsticks {
+                    player_idx: idx,
+                    card: chosen,
+                });
+                if let Some(card) = chosen {
+                    // chopsticks go back to the hand that will be passed
+                    hand.remove_card(card);
+                    hand.add_card(special);
+                    remove_played_card(&mut played_cards[idx], special);
+                    points.remove_card(idx, special);
+                    turn_cards[idx].push(card);
+                }
+            }
+            Spoon(_) => {
+                let request =
+                    players
+                        .get_player_mut(idx)
+                        .use_spoon(hand, idx, played_cards, context.rng);
+                let found = request.and_then(|request| {
+                    HandsView::new(hands, turn as isize).find_requested_card(idx, request)
+                });
+                context.log.push(|| GameEvent::Spoon {
+                    player_idx: idx,
+                    request,
+                    giver_idx: found.map(|(other_idx, _)| other_idx),
+                    card: found.map(|(_, card)| card),
+                });
+                if request.is_some() {
+                    if let Some((other_idx, card)) = found {
+                        // the spoon goes to the hand of the player giving the card
+                        let other_hand = HandsView::get_hand_mut(hands, turn as isize, other_idx);
+                        other_hand.remove_card(card);
+                        other_hand.add_card(special);
+                        turn_cards[idx].push(card);
+                    }
+                    remove_played_card(&mut played_cards[idx], special);
+                    points.remove_card(idx, special);
+                }
+            }
+            Menu(_) => {
+                // the menu is discarded, options which are menus can't be chosen
+                remove_played_card(&mut turn_cards[idx], special);
+                let mut drawn = deck.split_off(deck.len() - deck.len().min(MENU_OPTIONS));
+                let mut options = CardSet::new();
+                for card in drawn.iter().filter(|c| !matches!(c, Menu(_))) {
+                    options.add_card(*card);
+                }
+                let chosen = if options.is_empty() {
+                    None
+                } else {
+                    Some(players.get_player_mut(idx).choose_from_menu(
+                        &options,
+                        idx,
+                        played_cards,
+                        context.rng,
+                    ))
+                };
+                context.log.push(|| GameEvent::Menu {
+                    player_idx: idx,
+                    options: options.flatten(),
+                    card: chosen,
+                });
+                if let Some(card) = chosen {
+                    remove_played_card(&mut drawn, card);
+                    turn_cards[idx].push(card);
+                }
+                deck.append(&mut drawn);
+                deck.shuffle(&mut context.deck_rng);
+            }
+            TakeoutBox(_) => {
+                // the takeout box is discarded, flipped cards are replaced with it for marking
+                remove_played_card(&mut turn_cards[idx], special);
+                let mut flipped =
+                    players
+                        .get_player_mut(idx)
+                        .use_takeout_box(idx, played_cards, context.rng);
+                flipped.sort();
+                flipped.dedup();
+                context.log.push(|| GameEvent::TakeoutBox {
+                    player_idx: idx,
+                    flipped: flipped.clone(),
+                });
+                for pos in flipped {
+                    let card = played_cards[idx][pos];
+                    if let TakeoutBox(_) = card {
+                        continue; // already flipped
+                    }
+                    points.flip_card(idx, pos, special);
+                    played_cards[idx][pos] = special;
+                }
+            }
+            _ => {}
+        }
+
+        // menus and takeout boxes played thanks to other special actions have to be resolved too
+        for (rank, card) in turn_cards[idx]
+            .iter()
+            .filter(|c| matches!(c, Menu(_) | TakeoutBox(_)))
+            .filter_map(|c| c.get_special_rank().map(|rank| (rank, *c)))
+        {
+            if !specials.contains(&(rank, idx, card)) {
+                specials.push((rank, idx, card));
+            }
+        }
+        specials.sort_by_key(|(rank, idx, _)| Reverse((*rank, *idx)));
+    }
+
+    // special orders become copies of already played cards
+    for (idx, cards) in turn_cards.iter_mut().enumerate() {
+        for played_card in cards.iter_mut().filter(|c| **c == SpecialOrder) {
+            let copy = players.get_player_mut(idx).choose_special_order_copy(
+                idx,
+                played_cards,
+                context.rng,
+            );
+            context.log.push(|| GameEvent::SpecialOrder {
+                player_idx: idx,
+                card: copy,
+            });
+            if let Some(card) = copy {
+                assert!(
+                    card.can_be_copied() && played_cards[idx].contains(&card),
+                    "Special order can't copy {:?}!",
+                    card
+                );
+                *played_card = card;
+            }
+        }
+    }
+
+    // all miso soups are discarded if more than one was played this turn
+    let miso_count = turn_cards
+        .iter()
+        .flatten()
+        .filter(|c| **c == MisoSoup)
+        .count();
+    if miso_count > 1 {
+        for cards in turn_cards.iter_mut() {
+            cards.retain(|c| *c != MisoSoup);
+        }
+    }
+
+    for (player_played_cards, cards) in played_cards.iter_mut().zip(turn_cards.iter()) {
+        player_played_cards.extend(cards);
+    }
+    turn_cards
+}
+
+fn remove_played_card(played_cards: &mut CardVec, card: Card) {
+    // unwrap -> panic if the card is not on the table
+    let position = played_cards.iter().rposition(|c| *c == card).unwrap();
+    played_cards.remove(position);
+}
+
+// The game is fully determined by the menu, players and the rng state,
+// so simulating with a rng seeded with the same seed gives the same game.
+pub fn simulate<P>(menu: &Menu, players: &mut P, rng: &mut StdRng) -> Vec<isize>
+where
+    P: Players,
+{
+    simulate_with_ruleset(Ruleset::Party, menu, players, rng)
+}
+
+// Checks the menu before simulating, so an invalid one gives an error
+// instead of a panic in the middle of the game.
+pub fn try_simulate<P>(
+    menu: &Menu,
+    players: &mut P,
+    rng: &mut StdRng,
+) -> Result<Vec<isize>, MenuError>
+where
+    P: Players,
+{
+    validate_menu(menu, players.count())?;
+    Ok(simulate(menu, players, rng))
+}
+
+// Simulates the original Sushi Go with its fixed deck.
+pub fn simulate_original<P>(players: &mut P, rng: &mut StdRng) -> Vec<isize>
+where
+    P: Players,
+{
+    let menu = cards::ORIGINAL_CARDS.iter().cloned().collect::<Menu>();
+    simulate_with_ruleset(Ruleset::Original, &menu, players, rng)
+}
+
+pub fn simulate_with_ruleset<P>(
+    ruleset: Ruleset,
+    menu: &Menu,
+    players: &mut P,
+    rng: &mut StdRng,
+) -> Vec<isize>
+where
+    P: Players,
+{
+    simulate_in_context(
+        ruleset,
+        menu,
+        players,
+        &mut GameContext::new(rng, EventLog::default()),
+    )
+}
+
+// Simulates the game seeded with the seed and records everything that happened in it,
+// the log can be replayed with events::replay.
+pub fn simulate_with_log<P>(ruleset: Ruleset, menu: &Menu, players: &mut P, seed: u64) -> GameLog
+where
+    P: Players,
+{
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut context = GameContext::new(&mut rng, EventLog::enabled());
+    let scores = simulate_in_context(ruleset, menu, players, &mut context);
+    let mut menu = menu.iter().cloned().collect::<CardVec>();
+    menu.sort();
+    GameLog {
+        ruleset,
+        menu,
+        seed,
+        players_count: players.count(),
+        with_dummy: uses_dummy(ruleset, players.count()),
+        events: context.log.into_events(),
+        scores,
+    }
+}
+
+// Only Sushi Go Party plays 2 players games with a dummy, the original game is played head to head.
+fn uses_dummy(ruleset: Ruleset, players_count: usize) -> bool {
+    ruleset == Ruleset::Party && players_count == 2
+}
 
-pub fn simulate<P>(menu: &Menu, players: &mut P) -> Vec<isize>
+fn simulate_in_context<P>(
+    ruleset: Ruleset,
+    menu: &Menu,
+    players: &mut P,
+    context: &mut GameContext,
+) -> Vec<isize>
 where
     P: Players,
 {
-    let players_count = P::COUNT;
-    let cards_per_player = cards::get_cards_per_player(players_count);
+    if uses_dummy(ruleset, players.count()) {
+        // 2 players variant: the game is played as if there were 3 players,
+        // the third one is a dummy and its score is dropped
+        let mut scores = simulate_game(
+            ruleset,
+            menu,
+            &mut WithDummy {
+                players,
+                dummy: DummyPlayer::default(),
+            },
+            true,
+            context,
+        );
+        scores.pop();
+        scores
+    } else {
+        simulate_game(ruleset, menu, players, false, context)
+    }
+}
+
+fn new_point_calculator(
+    ruleset: Ruleset,
+    players_count: usize,
+    with_dummy: bool,
+    uramaki_position: usize,
+) -> PointCalculator {
+    let points =
+        PointCalculator::with_capacity(players_count, uramaki_position).with_ruleset(ruleset);
+    if with_dummy {
+        points.with_dummy()
+    } else {
+        points
+    }
+}
+
+pub(crate) fn simulate_game<P>(
+    ruleset: Ruleset,
+    menu: &Menu,
+    players: &mut P,
+    with_dummy: bool,
+    context: &mut GameContext,
+) -> Vec<isize>
+where
+    P: Players,
+{
+    let players_count = players.count();
+    let real_players_count = if with_dummy {
+        players_count - 1
+    } else {
+        players_count
+    };
+    let cards_per_player = ruleset.get_cards_per_player(real_players_count);
     let mut scores = vec![0isize; players_count];
+    let mut desserts = CardSet::desserts_from_menu(menu);
+    let mut played_desserts: Vec<CardVec> = repeat_with(CardVec::new).take(players_count).collect();
     let mut uramaki_position = 0;
-    let mut played_desserts: Vec<CardVec> =
-        repeat_with(|| CardVec::new()).take(players_count).collect();
+    // original rules use a single deck for the whole game
+    let mut game_deck = match ruleset {
+        Ruleset::Party => CardVec::new(),
+        Ruleset::Original => {
+            let mut deck = CardSet::original_deck().flatten();
+            deck.shuffle(&mut context.deck_rng);
+            deck
+        }
+    };
 
     for round in 1..=ROUNDS_COUNT {
-        // init hands and played cards
-        let mut hands: Vec<CardSet> = repeat_with(|| CardSet::new()).take(players_count).collect();
+        // init hands, played cards and points for the round
+        // (uramaki awards continue from the ones given in previous rounds)
+        let mut hands: Vec<CardSet> = repeat_with(CardSet::new).take(players_count).collect();
         let mut played_cards: Vec<CardVec> =
             repeat_with(|| CardVec::with_capacity(cards_per_player))
                 .take(players_count)
                 .collect();
+        let mut points = new_point_calculator(ruleset, players_count, with_dummy, uramaki_position);
 
-        // deal cards
-        let mut cards = CardSet::from_menu(menu, players_count, round).flatten();
-        cards.shuffle(&mut rand::thread_rng());
-        let mut dealer = cards.into_iter();
-        for i in 0..players_count {
+        // deal cards (with desserts for the round drawn from the pile),
+        // cards that were not dealt stay in the deck
+        let mut deck = match ruleset {
+            Ruleset::Party => {
+                let mut deck = CardSet::from_menu(menu)
+                    .add_set(&desserts.draw_random_cards(
+                        cards::get_dessert_count(players_count, round),
+                        &mut context.deck_rng,
+                    ))
+                    .flatten();
+                deck.shuffle(&mut context.deck_rng);
+                deck
+            }
+            Ruleset::Original => std::mem::take(&mut game_deck),
+        };
+        for hand in hands.iter_mut() {
             for _ in 0..cards_per_player {
-                hands[i].add_card(dealer.next().unwrap());
+                hand.add_card(deck.pop().unwrap());
             }
         }
+        context.log.push(|| GameEvent::Deal {
+            round,
+            hands: hands.iter().map(|hand| hand.flatten()).collect(),
+        });
 
         // play all turns
         for turn in 0..cards_per_player {
-            let hands_view = HandsView::new(&hands, turn as isize);
-            let mut played_now = [None; MAX_PLAYERS];
-            players.play(&hands_view, &played_cards, &mut played_now);
-            for (idx, (played_card_option, player_played_cards)) in
-                played_now.iter().zip(played_cards.iter_mut()).enumerate()
-            {
-                // unwrap played card, if None -> panic
-                let played_card = played_card_option.unwrap();
-                HandsView::get_hand_mut(&mut hands, turn as isize, idx).remove_card(played_card); // FIXME: get liftimes in order
-                player_played_cards.push(played_card);
+            let turn_cards = play_turn(
+                players,
+                &mut hands,
+                &mut deck,
+                turn,
+                &mut played_cards,
+                &mut points,
+                context,
+            );
+            for event in points.apply_turn(&turn_cards) {
+                let ScoringEvent::Uramaki { player_idx, points } = event;
+                context
+                    .log
+                    .push(|| GameEvent::UramakiScore { player_idx, points });
+                // scored uramaki are discarded
+                played_cards[player_idx].retain(|c| !matches!(c, Uramaki(_)));
+            }
+            if turn + 1 < cards_per_player {
+                let hands_view = HandsView::new(&hands, turn as isize + 1);
+                context.log.push(|| GameEvent::Pass {
+                    turn,
+                    hands: (0..players_count)
+                        .map(|idx| hands_view.get_hand(idx).flatten())
+                        .collect(),
+                });
             }
         }
         // make sure all cards have been played
+        // (only chopsticks and spoons used in the last turn can be left)
         for hand in hands {
-            assert_eq!(hand.len(), 0);
+            assert!(hand
+                .iter()
+                .all(|(card, _)| matches!(card, Chopsticks(_) | Spoon(_))));
+        }
+
+        match ruleset {
+            Ruleset::Party => {
+                // desserts that were not dealt go back to the pile
+                for card in deck.into_iter().filter(|c| c.is_dessert()) {
+                    desserts.add_card(card);
+                }
+            }
+            Ruleset::Original => game_deck = deck,
         }
 
-        // count the points
-        let mut points = PointCalculator::with_capacity(players_count, uramaki_position);
-        points.apply_cards(&played_cards);
-        for (score, points_count) in scores.iter_mut().zip(points.calculate_points(menu, true).iter()) {
-            *score += points_count;
+        // count the points (desserts are scored only at the end of the game)
+        let round_points = points
+            .calculate_breakdown(menu, true)
+            .into_iter()
+            .map(ScoreBreakdown::without_desserts)
+            .collect::<Vec<ScoreBreakdown>>();
+        for (score, breakdown) in scores.iter_mut().zip(round_points.iter()) {
+            *score += breakdown.total();
         }
-        uramaki_position = points.has_uramaki_scores();
+        context.log.push(|| GameEvent::RoundScore {
+            round,
+            points: round_points,
+        });
+        // the next round continues with the awards nobody got yet
+        uramaki_position = points.uramaki_position_after_round();
 
         // keep the played desserts
         for (desserts_stash, player_played_cards) in
@@ -163,11 +818,20 @@ where
     }
 
     // last round is finished -> count the dessert points
-    let mut points = PointCalculator::with_capacity(players_count, 0);
-    points.apply_cards(&played_desserts);
-    for (score, points_count) in scores.iter_mut().zip(points.calculate_points(menu, true).iter()) {
-        *score += points_count;
+    let mut points = new_point_calculator(ruleset, players_count, with_dummy, 0);
+    // desserts don't depend on the order they were played in
+    points.apply_turn(&played_desserts);
+    let dessert_points = points
+        .calculate_breakdown(menu, true)
+        .into_iter()
+        .map(ScoreBreakdown::desserts_only)
+        .collect::<Vec<ScoreBreakdown>>();
+    for (score, breakdown) in scores.iter_mut().zip(dessert_points.iter()) {
+        *score += breakdown.total();
     }
+    context.log.push(|| GameEvent::DessertScore {
+        points: dessert_points,
+    });
 
     scores
 }
@@ -175,8 +839,14 @@ where
 #[derive(Clone, Copy, Debug, Default)]
 pub struct RandomPlayer {}
 impl Player for RandomPlayer {
-    fn play(&mut self, hand: &CardSet, _player_idx: usize, _played_cards: &[CardVec]) -> Card {
-        hand.random_card().unwrap()
+    fn play(
+        &mut self,
+        hand: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        rng: &mut StdRng,
+    ) -> Card {
+        hand.random_card(rng).unwrap()
     }
 }
 
@@ -204,12 +874,570 @@ impl PreferedCardsPlayer {
 }
 
 impl Player for PreferedCardsPlayer {
-    fn play(&mut self, hand: &CardSet, _player_idx: usize, _played_cards: &[CardVec]) -> Card {
+    fn play(
+        &mut self,
+        hand: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        rng: &mut StdRng,
+    ) -> Card {
         for card in self.preferences.iter() {
             if hand.contains_card(*card) {
                 return *card;
             }
         }
-        hand.random_card().unwrap()
+        hand.random_card(rng).unwrap()
     }
+
+    fn use_chopsticks(
+        &mut self,
+        hand: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Option<Card> {
+        self.preferences
+            .iter()
+            .cloned()
+            .find(|card| hand.contains_card(*card))
+    }
+
+    fn use_spoon(
+        &mut self,
+        _hand: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Option<SpoonRequest> {
+        self.preferences
+            .first()
+            .map(|card| SpoonRequest::Card(*card))
+    }
+
+    fn choose_from_menu(
+        &mut self,
+        options: &CardSet,
+        player_idx: usize,
+        played_cards: &[CardVec],
+        rng: &mut StdRng,
+    ) -> Card {
+        self.play(options, player_idx, played_cards, rng)
+    }
+
+    fn choose_special_order_copy(
+        &mut self,
+        player_idx: usize,
+        played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Option<Card> {
+        self.preferences
+            .iter()
+            .cloned()
+            .find(|card| card.can_be_copied() && played_cards[player_idx].contains(card))
+    }
+
+    fn use_takeout_box(
+        &mut self,
+        player_idx: usize,
+        played_cards: &[CardVec],
+        _rng: &mut StdRng,
+    ) -> Vec<usize> {
+        // flip everything that is not preferred
+        played_cards[player_idx]
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| !self.preferences.contains(card))
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+fn test_rng() -> StdRng {
+    use rand::SeedableRng;
+    StdRng::seed_from_u64(0)
+}
+
+#[cfg(test)]
+fn hands_from(hands: &[&[Card]]) -> Vec<CardSet> {
+    hands
+        .iter()
+        .map(|cards| {
+            let mut set = CardSet::new();
+            for card in cards.iter() {
+                set.add_card(*card);
+            }
+            set
+        })
+        .collect()
+}
+
+// Plays a turn for 4 players with the cards already on the table and returns
+// the cards played in the turn, the table and the hands after it.
+#[cfg(test)]
+fn run_turn(
+    preferences: [&[Card]; 4],
+    hands: &[&[Card]],
+    mut played_cards: Vec<CardVec>,
+    deck: &mut CardVec,
+) -> (Vec<CardVec>, Vec<CardVec>, Vec<CardSet>) {
+    let mut players = (
+        PreferedCardsPlayer::new(preferences[0].to_vec()),
+        PreferedCardsPlayer::new(preferences[1].to_vec()),
+        PreferedCardsPlayer::new(preferences[2].to_vec()),
+        PreferedCardsPlayer::new(preferences[3].to_vec()),
+    );
+    let mut hands = hands_from(hands);
+    let mut points = PointCalculator::with_capacity(4, 0);
+    points.apply_cards(&played_cards);
+    let turn_cards = play_turn(
+        &mut players,
+        &mut hands,
+        deck,
+        0,
+        &mut played_cards,
+        &mut points,
+        &mut GameContext::new(&mut test_rng(), EventLog::default()),
+    );
+    (turn_cards, played_cards, hands)
+}
+
+#[test]
+fn test_play_turn_chopsticks() {
+    let (turn_cards, played_cards, hands) = run_turn(
+        [
+            &[Nigiri(3)],
+            &[Nigiri(3)],
+            &[Nigiri(2), Nigiri(1)],
+            &[Nigiri(1)],
+        ],
+        &[
+            &[Nigiri(3), Nigiri(1)],
+            &[Nigiri(3), Nigiri(1)],
+            &[Nigiri(2), Nigiri(1)],
+            &[Nigiri(1), Nigiri(1)],
+        ],
+        vec![vec![Chopsticks(2)], vec![], vec![Chopsticks(1)], vec![]],
+        &mut vec![],
+    );
+    assert_eq!(
+        turn_cards,
+        vec![
+            vec![Nigiri(3)],
+            vec![Nigiri(3)],
+            vec![Nigiri(2), Nigiri(1)],
+            vec![Nigiri(1)]
+        ]
+    );
+    // first player has no preferred card left so the chopsticks are kept
+    assert_eq!(played_cards[0], vec![Chopsticks(2), Nigiri(3)]);
+    assert_eq!(played_cards[2], vec![Nigiri(2), Nigiri(1)]);
+    assert_eq!(hands[2], hands_from(&[&[Chopsticks(1)]])[0]);
+}
+
+#[test]
+fn test_play_turn_spoon() {
+    let (turn_cards, played_cards, hands) = run_turn(
+        [&[Nigiri(3)], &[Tempura], &[], &[Tempura]],
+        &[
+            &[Nigiri(1), Nigiri(1)],
+            &[Tempura, Eel],
+            &[Nigiri(3), Nigiri(3)],
+            &[Nigiri(3), Nigiri(3)],
+        ],
+        vec![vec![Spoon(5)], vec![], vec![], vec![Spoon(4)]],
+        &mut vec![],
+    );
+    // Spoon(4) is resolved first: nobody else has tempura at that point
+    // so the spoon is discarded, Spoon(5) takes squid from the third player
+    assert_eq!(turn_cards[0], vec![Nigiri(1), Nigiri(3)]);
+    assert_eq!(turn_cards[3], vec![Nigiri(3)]);
+    assert_eq!(played_cards[0], vec![Nigiri(1), Nigiri(3)]);
+    assert_eq!(played_cards[3], vec![Nigiri(3)]);
+    assert_eq!(hands[2], hands_from(&[&[Spoon(5)]])[0]);
+}
+
+#[test]
+fn test_spoon_request() {
+    assert!(SpoonRequest::Card(Nigiri(2)).matches(Nigiri(2)));
+    assert!(!SpoonRequest::Card(Nigiri(2)).matches(Nigiri(3)));
+    assert!(SpoonRequest::Kind(Nigiri(2)).matches(Nigiri(3)));
+    assert!(!SpoonRequest::Kind(Nigiri(2)).matches(Wasabi));
+}
+
+#[test]
+fn test_play_turn_menu() {
+    let mut deck = vec![Tempura, Menu(9), Sashimi, Nigiri(1), Nigiri(3)];
+    let (turn_cards, played_cards, _) = run_turn(
+        [&[Menu(8)], &[Menu(7)], &[], &[]],
+        &[&[Menu(8), Tofu], &[Menu(7), Tofu], &[Eel, Eel], &[Eel, Eel]],
+        vec![vec![]; 4],
+        &mut deck,
+    );
+    // Menu(7) is resolved first, both menus are discarded
+    assert_eq!(turn_cards[0].len(), 1);
+    assert_eq!(turn_cards[1].len(), 1);
+    assert_eq!(deck.len(), 3);
+    assert!(deck.contains(&Menu(9)));
+    assert!(!played_cards.iter().flatten().any(|c| matches!(c, Menu(_))));
+}
+
+#[test]
+fn test_play_turn_takeout_box() {
+    let (turn_cards, played_cards, _) = run_turn(
+        [&[TakeoutBox(11), Nigiri(3)], &[], &[], &[]],
+        &[
+            &[TakeoutBox(11), Eel],
+            &[Eel, Eel],
+            &[Eel, Eel],
+            &[Eel, Eel],
+        ],
+        vec![vec![Nigiri(3), Eel, Tofu], vec![], vec![], vec![]],
+        &mut vec![],
+    );
+    assert_eq!(turn_cards[0], vec![]);
+    assert_eq!(
+        played_cards[0],
+        vec![Nigiri(3), TakeoutBox(11), TakeoutBox(11)]
+    );
+}
+
+#[test]
+fn test_play_turn_special_order() {
+    let (turn_cards, _, _) = run_turn(
+        [
+            &[SpecialOrder, Uramaki(5)],
+            &[SpecialOrder, Uramaki(5)],
+            &[],
+            &[],
+        ],
+        &[
+            &[SpecialOrder, Eel],
+            &[SpecialOrder, Eel],
+            &[Eel, Eel],
+            &[Eel, Eel],
+        ],
+        vec![vec![Uramaki(5), Tofu], vec![Tofu], vec![], vec![]],
+        &mut vec![],
+    );
+    // second player has nothing preferred to copy -> rainbow card
+    assert_eq!(turn_cards[0], vec![Uramaki(5)]);
+    assert_eq!(turn_cards[1], vec![SpecialOrder]);
+}
+
+#[test]
+fn test_play_turn_miso_soup() {
+    let (turn_cards, _, _) = run_turn(
+        [&[MisoSoup], &[MisoSoup], &[Eel], &[Eel]],
+        &[
+            &[MisoSoup, Eel],
+            &[MisoSoup, Eel],
+            &[MisoSoup, Eel],
+            &[Eel, Eel],
+        ],
+        vec![vec![]; 4],
+        &mut vec![],
+    );
+    assert_eq!(turn_cards, vec![vec![], vec![], vec![Eel], vec![Eel]]);
+
+    // single miso soup stays
+    let (turn_cards, _, _) = run_turn(
+        [&[MisoSoup], &[Eel], &[Eel], &[Eel]],
+        &[&[MisoSoup, Eel], &[Eel], &[Eel], &[Eel]],
+        vec![vec![]; 4],
+        &mut vec![],
+    );
+    assert_eq!(turn_cards[0], vec![MisoSoup]);
+}
+
+#[test]
+fn test_simulate_players_count() {
+    let menu = [
+        Nigiri(1),
+        Nigiri(2),
+        Nigiri(3),
+        Maki(1),
+        Maki(2),
+        Maki(3),
+        Tempura,
+        Sashimi,
+        Dumpling,
+        Eel,
+        Pudding,
+    ]
+    .iter()
+    .cloned()
+    .collect::<Menu>();
+    let p = RandomPlayer::default();
+    assert_eq!(simulate(&menu, &mut (p, p), &mut test_rng()).len(), 2);
+    assert_eq!(simulate(&menu, &mut (p, p, p), &mut test_rng()).len(), 3);
+    assert_eq!(
+        simulate(&menu, &mut (p, p, p, p, p), &mut test_rng()).len(),
+        5
+    );
+    assert_eq!(
+        simulate(&menu, &mut (p, p, p, p, p, p), &mut test_rng()).len(),
+        6
+    );
+    assert_eq!(
+        simulate(&menu, &mut (p, p, p, p, p, p, p), &mut test_rng()).len(),
+        7
+    );
+    assert_eq!(
+        simulate(&menu, &mut (p, p, p, p, p, p, p, p), &mut test_rng()).len(),
+        8
+    );
+}
+
+#[test]
+fn test_simulate_table() {
+    let menu = [
+        Nigiri(1),
+        Nigiri(2),
+        Nigiri(3),
+        Maki(1),
+        Maki(2),
+        Maki(3),
+        Tempura,
+    ]
+    .iter()
+    .cloned()
+    .collect::<Menu>();
+    let mut table = Table(vec![
+        Box::new(RandomPlayer::default()),
+        Box::new(PreferedCardsPlayer::new_best_nigiri()),
+        Box::new(PreferedCardsPlayer::new_nigiri_master()),
+    ]);
+    assert_eq!(table.count(), 3);
+    assert_eq!(simulate(&menu, &mut table, &mut test_rng()).len(), 3);
+}
+
+#[test]
+fn test_try_simulate() {
+    let menu = crate::menus::get_official_menu("Master Menu")
+        .unwrap()
+        .get_menu();
+    let p = RandomPlayer::default();
+    assert_eq!(
+        try_simulate(&menu, &mut (p, p, p), &mut test_rng()).map(|s| s.len()),
+        Ok(3)
+    );
+    // spoons need at least 3 players
+    assert_eq!(
+        try_simulate(&menu, &mut (p, p), &mut test_rng()),
+        Err(MenuError::UnsupportedPlayersCount {
+            card: Spoon(4),
+            players: 2
+        })
+    );
+}
+
+#[test]
+fn test_simulate_seeded() {
+    use rand::SeedableRng;
+    let menu = crate::menus::get_official_menu("Master Menu")
+        .unwrap()
+        .get_menu();
+    let p = RandomPlayer::default();
+    let games = (0..20)
+        .map(|seed| simulate(&menu, &mut (p, p, p, p), &mut StdRng::seed_from_u64(seed)))
+        .collect::<Vec<Vec<isize>>>();
+    for (seed, scores) in games.iter().enumerate() {
+        assert_eq!(
+            &simulate(
+                &menu,
+                &mut (p, p, p, p),
+                &mut StdRng::seed_from_u64(seed as u64)
+            ),
+            scores
+        );
+    }
+    assert!(games.iter().any(|scores| *scores != games[0]));
+}
+
+// Takes every decision at random, special actions included.
+#[cfg(test)]
+#[derive(Debug)]
+struct RandomActionsPlayer {}
+
+#[cfg(test)]
+impl Player for RandomActionsPlayer {
+    fn play(
+        &mut self,
+        hand: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        rng: &mut StdRng,
+    ) -> Card {
+        hand.random_card(rng).unwrap()
+    }
+
+    fn use_chopsticks(
+        &mut self,
+        hand: &CardSet,
+        _player_idx: usize,
+        _played_cards: &[CardVec],
+        rng: &mut StdRng,
+    ) -> Option<Card> {
+        if hand.is_empty() {
+            None
+        } else {
+            hand.random_card(rng)
+        }
+    }
+
+    fn use_spoon(
+        &mut self,
+        hand: &CardSet,
+        player_idx: usize,
+        played_cards: &[CardVec],
+        rng: &mut StdRng,
+    ) -> Option<SpoonRequest> {
+        self.use_chopsticks(hand, player_idx, played_cards, rng)
+            .map(SpoonRequest::Kind)
+    }
+
+    fn use_takeout_box(
+        &mut self,
+        player_idx: usize,
+        played_cards: &[CardVec],
+        rng: &mut StdRng,
+    ) -> Vec<usize> {
+        use rand::Rng;
+        (0..played_cards[player_idx].len())
+            .filter(|_| rng.gen())
+            .collect()
+    }
+
+    fn choose_special_order_copy(
+        &mut self,
+        player_idx: usize,
+        played_cards: &[CardVec],
+        rng: &mut StdRng,
+    ) -> Option<Card> {
+        let copyable = played_cards[player_idx]
+            .iter()
+            .cloned()
+            .filter(|card| card.can_be_copied())
+            .collect::<CardVec>();
+        copyable.choose(rng).cloned()
+    }
+}
+
+#[test]
+fn test_simulate_seeded_special_actions() {
+    let menu = [
+        Nigiri(1),
+        Uramaki(3),
+        Tempura,
+        Eel,
+        Chopsticks(1),
+        Spoon(4),
+        TakeoutBox(10),
+        SpecialOrder,
+        Pudding,
+    ]
+    .iter()
+    .flat_map(|card| card.get_variants())
+    .collect::<Menu>();
+    let new_table = || {
+        Table(
+            (0..4)
+                .map(|_| Box::new(RandomActionsPlayer {}) as Box<dyn Player + Send>)
+                .collect(),
+        )
+    };
+    for seed in 0..10 {
+        let log = simulate_with_log(Ruleset::Party, &menu, &mut new_table(), seed);
+        assert_eq!(
+            simulate_with_log(Ruleset::Party, &menu, &mut new_table(), seed),
+            log
+        );
+        assert!(log
+            .events
+            .iter()
+            .any(|event| matches!(event, GameEvent::Spoon { .. })));
+    }
+}
+
+#[test]
+fn test_simulate_original() {
+    let p = RandomPlayer::default();
+    assert_eq!(simulate_original(&mut (p, p), &mut test_rng()).len(), 2);
+    assert_eq!(
+        simulate_original(&mut (p, p, p, p, p), &mut test_rng()).len(),
+        5
+    );
+}
+
+// Round and dessert scores logged in games of random players for the first 10 seeds.
+#[cfg(test)]
+fn logged_scores(
+    ruleset: Ruleset,
+    menu: &Menu,
+    players_count: usize,
+) -> (Vec<ScoreBreakdown>, Vec<ScoreBreakdown>) {
+    let mut round_scores = Vec::new();
+    let mut dessert_scores = Vec::new();
+    for seed in 0..10 {
+        let mut table = Table(
+            (0..players_count)
+                .map(|_| Box::new(RandomPlayer::default()) as Box<dyn Player + Send>)
+                .collect(),
+        );
+        for event in simulate_with_log(ruleset, menu, &mut table, seed).events {
+            match event {
+                GameEvent::RoundScore { points, .. } => round_scores.extend(points),
+                GameEvent::DessertScore { points } => dessert_scores.extend(points),
+                _ => {}
+            }
+        }
+    }
+    (round_scores, dessert_scores)
+}
+
+#[test]
+fn test_desserts_scored_at_game_end() {
+    let menu = crate::menus::get_official_menu("Master Menu")
+        .unwrap()
+        .get_menu();
+    let (round_scores, _) = logged_scores(Ruleset::Party, &menu, 3);
+    assert!(round_scores.iter().all(|breakdown| breakdown.fruit == 0));
+}
+
+#[test]
+fn test_original_no_dummy() {
+    let menu = cards::ORIGINAL_CARDS.iter().cloned().collect::<Menu>();
+    let p = RandomPlayer::default();
+    let log = simulate_with_log(Ruleset::Original, &menu, &mut (p, p), 0);
+    assert!(!log.with_dummy);
+    assert_eq!(log.scores.len(), 2);
+    match &log.events[0] {
+        GameEvent::Deal { hands, .. } => {
+            assert_eq!(hands.len(), 2);
+            assert!(hands.iter().all(|hand| hand.len() == 10));
+        }
+        event => panic!("Expected a deal, got {:?}", event),
+    }
+    assert_eq!(crate::events::replay(&log), Ok(log.scores.clone()));
+}
+
+#[test]
+fn test_original_pudding_at_game_end() {
+    let menu = cards::ORIGINAL_CARDS.iter().cloned().collect::<Menu>();
+    let (round_scores, _) = logged_scores(Ruleset::Original, &menu, 4);
+    assert!(round_scores.iter().all(|breakdown| breakdown.pudding == 0));
+}
+
+#[test]
+fn test_dessert_score_breakdown() {
+    let menu = crate::menus::get_official_menu("Master Menu")
+        .unwrap()
+        .get_menu();
+    let (_, dessert_scores) = logged_scores(Ruleset::Party, &menu, 3);
+    assert_eq!(dessert_scores.len(), 30);
+    // only the dessert categories are scored at the end of the game
+    assert!(dessert_scores
+        .iter()
+        .all(|breakdown| *breakdown == breakdown.desserts_only()));
 }
@@ -1,19 +1,20 @@
 use crate::cards;
 use crate::cards::Card::*;
-use crate::cards::{Card, CardColor, CardVec, Menu};
-use std::collections::{HashMap, HashSet};
+use crate::cards::{Card, CardColor, CardVec, Menu, Ruleset};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Clone, Debug, Default)]
 struct PointCalculatorPlayerState {
-    nigiri_points: usize,
-    wasabi_stack: usize,
+    played_cards: CardVec, // nigiri are scored by their order with wasabi
     maki_score: usize,
     temaki_count: usize,
     uramaki_score: usize,
+    uramaki_points: isize,
     dumpling_count: usize,
     edamame_count: usize,
     eel_count: usize,
-    onigiri_present: HashSet<(bool, bool)>,
+    onigiri_counts: HashMap<(bool, bool), usize>,
     miso_count: usize,
     sashimi_count: usize,
     tempura_count: usize,
@@ -28,38 +29,46 @@ struct PointCalculatorPlayerState {
 }
 
 impl PointCalculatorPlayerState {
-    fn apply_cards(&mut self, played_cards: &CardVec) {
-        for card in played_cards {
-            self.apply_card(*card);
-        }
+    fn apply_card(&mut self, card: Card) {
+        self.played_cards.push(card);
+        self.count_card(card);
     }
 
-    fn apply_card(&mut self, card: Card) {
+    // Removes the last played card of this kind.
+    fn remove_card(&mut self, card: Card) {
+        // unwrap -> panic if the card was not applied
+        let pos = self.played_cards.iter().rposition(|c| *c == card).unwrap();
+        self.played_cards.remove(pos);
+        self.uncount_card(card);
+    }
+
+    fn flip_card(&mut self, pos: usize, marker: Card) {
+        self.uncount_card(self.played_cards[pos]);
+        self.played_cards[pos] = marker;
+        self.count_card(marker);
+    }
+
+    fn count_card(&mut self, card: Card) {
         match card {
-            Nigiri(x) => {
-                self.nigiri_points += if self.wasabi_stack > 0 {
-                    self.wasabi_stack -= 1;
-                    x * 3
-                } else {
-                    x
-                }
-            }
-            Wasabi => self.wasabi_stack += 1,
+            Nigiri(_) | Wasabi => { /* scored by their order in played cards */ }
             Maki(x) => self.maki_score += x,
             Temaki => self.temaki_count += 1,
             Uramaki(x) => self.uramaki_score += x,
             Dumpling => self.dumpling_count += 1,
             Edamame => self.edamame_count += 1,
             Eel => self.eel_count += 1,
-            Onigiri(a, b) => {
-                self.onigiri_present.insert((a, b));
-            }
+            Onigiri(a, b) => *self.onigiri_counts.entry((a, b)).or_insert(0) += 1,
             MisoSoup => self.miso_count += 1,
             Sashimi => self.sashimi_count += 1,
             Tempura => self.tempura_count += 1,
             Tofu => self.tofu_count += 1,
             SoySauce => self.soy_sauce_count += 1,
-            TakeoutBox(_) => self.taken_out_count += 1, // note: cards are being replaced with this one for marking
+            TakeoutBox(_) => {
+                // note: flipped cards are being replaced with this one for marking,
+                // they are face down so they don't have any color
+                self.taken_out_count += 1;
+                return;
+            }
             Tea => self.tea_count += 1,
             GreenTeaIceCream => self.ice_cream_count += 1,
             Pudding => self.pudding_count += 1,
@@ -69,7 +78,8 @@ impl PointCalculatorPlayerState {
                 self.fruits_counts.2 += c;
             }
             Chopsticks(_) | Spoon(_) => { /* no-op */ }
-            Menu(_) | SpecialOrder => {
+            SpecialOrder => { /* nothing was copied, counts only as rainbow color */ }
+            Menu(_) => {
                 panic!("{:?} shouldn't be played to the table!", card);
             }
         }
@@ -79,24 +89,168 @@ impl PointCalculatorPlayerState {
             .or_insert(1);
     }
 
+    // Reverts what count_card did for a card that left the table.
+    fn uncount_card(&mut self, card: Card) {
+        match card {
+            Nigiri(_) | Wasabi => { /* scored by their order in played cards */ }
+            Maki(x) => self.maki_score -= x,
+            Temaki => self.temaki_count -= 1,
+            Uramaki(x) => self.uramaki_score -= x,
+            Dumpling => self.dumpling_count -= 1,
+            Edamame => self.edamame_count -= 1,
+            Eel => self.eel_count -= 1,
+            Onigiri(a, b) => {
+                // unwrap -> panic if the card was not applied
+                *self.onigiri_counts.get_mut(&(a, b)).unwrap() -= 1;
+            }
+            MisoSoup => self.miso_count -= 1,
+            Sashimi => self.sashimi_count -= 1,
+            Tempura => self.tempura_count -= 1,
+            Tofu => self.tofu_count -= 1,
+            SoySauce => self.soy_sauce_count -= 1,
+            TakeoutBox(_) => {
+                self.taken_out_count -= 1;
+                return;
+            }
+            Tea => self.tea_count -= 1,
+            GreenTeaIceCream => self.ice_cream_count -= 1,
+            Pudding => self.pudding_count -= 1,
+            Fruit(a, b, c) => {
+                self.fruits_counts.0 -= a;
+                self.fruits_counts.1 -= b;
+                self.fruits_counts.2 -= c;
+            }
+            Chopsticks(_) | Spoon(_) | SpecialOrder => { /* no-op */ }
+            Menu(_) => {
+                panic!("{:?} shouldn't be on the table!", card);
+            }
+        }
+        self.remove_color(card.get_color(), 1);
+    }
+
+    fn remove_color(&mut self, color: CardColor, count: usize) {
+        // unwrap -> panic if the cards were not applied
+        let color_count = self.color_counts.get_mut(&color).unwrap();
+        *color_count -= count;
+        if *color_count == 0 {
+            self.color_counts.remove(&color);
+        }
+    }
+
+    fn get_nigiri_points(&self) -> usize {
+        let nigiri_value = |card: &Card| match card {
+            Nigiri(x) => *x,
+            _ => 0,
+        };
+        // nigiri on wasabi are worth 3 times as much (so 2 times more on top)
+        self.played_cards.iter().map(nigiri_value).sum::<usize>()
+            + cards::get_wasabi_slots(&self.played_cards)
+                .iter()
+                .flatten()
+                .map(|card| nigiri_value(card) * 2)
+                .sum::<usize>()
+    }
+
     fn has_uramaki_score(&self) -> bool {
         self.uramaki_score >= 10 // 10 - score needed for uramaki
     }
+
+    fn uramaki_score_if_scoring(&self) -> usize {
+        if self.has_uramaki_score() {
+            self.uramaki_score
+        } else {
+            0
+        }
+    }
+}
+
+// Points of a player for each scoring category.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ScoreBreakdown {
+    pub nigiri: isize,
+    pub maki: isize,
+    pub temaki: isize,
+    pub uramaki: isize,
+    pub dumpling: isize,
+    pub edamame: isize,
+    pub eel: isize,
+    pub onigiri: isize,
+    pub miso: isize,
+    pub sashimi: isize,
+    pub tempura: isize,
+    pub tofu: isize,
+    pub soy_sauce: isize,
+    pub takeout: isize,
+    pub tea: isize,
+    pub ice_cream: isize,
+    pub pudding: isize,
+    pub fruit: isize,
+}
+
+impl ScoreBreakdown {
+    // Desserts are scored only at the end of the game, rounds score the rest.
+    pub fn without_desserts(self) -> Self {
+        ScoreBreakdown {
+            ice_cream: 0,
+            pudding: 0,
+            fruit: 0,
+            ..self
+        }
+    }
+
+    pub fn desserts_only(self) -> Self {
+        ScoreBreakdown {
+            ice_cream: self.ice_cream,
+            pudding: self.pudding,
+            fruit: self.fruit,
+            ..ScoreBreakdown::default()
+        }
+    }
+
+    pub fn total(&self) -> isize {
+        self.nigiri
+            + self.maki
+            + self.temaki
+            + self.uramaki
+            + self.dumpling
+            + self.edamame
+            + self.eel
+            + self.onigiri
+            + self.miso
+            + self.sashimi
+            + self.tempura
+            + self.tofu
+            + self.soy_sauce
+            + self.takeout
+            + self.tea
+            + self.ice_cream
+            + self.pudding
+            + self.fruit
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ScoringEvent {
+    Uramaki { player_idx: usize, points: isize },
 }
 
 #[derive(Clone, Debug)]
 pub struct PointCalculator {
     states: Box<[PointCalculatorPlayerState]>,
     uramaki_position: usize,
+    with_dummy: bool,
+    ruleset: Ruleset,
 }
 
 impl PointCalculator {
     const MAKI_POINTS_2_5_PLAYERS: [isize; 3] = [6, 3, 0];
-    const MAKI_POINTS_6_8_PLAYERS: [isize; 3] = [6, 3, 0];
+    const MAKI_POINTS_6_8_PLAYERS: [isize; 3] = [6, 4, 2];
+    const URAMAKI_POINTS: [isize; 3] = [8, 5, 2];
     const TEMAKI_2_PLAYERS: (isize, isize) = (4, 0);
     const TEMAKI_3_8_PLAYERS: (isize, isize) = (4, -4);
     const PUDDING_2_PLAYERS: (isize, isize) = (6, 0);
     const PUDDING_3_8_PLAYERS: (isize, isize) = (6, -6);
+    const ORIGINAL_MAKI_POINTS: [isize; 2] = [6, 3];
 
     pub fn with_capacity(capacity: usize, uramaki_position: usize) -> Self {
         let mut states = Vec::with_capacity(capacity);
@@ -106,67 +260,149 @@ impl PointCalculator {
         PointCalculator {
             states: states.into_boxed_slice(),
             uramaki_position,
+            with_dummy: false,
+            ruleset: Ruleset::Party,
         }
     }
 
-    pub fn apply_cards(&mut self, played_cards: &[CardVec]) {
-        for (state, cards) in self.states.iter_mut().zip(played_cards.iter()) {
-            state.apply_cards(cards);
+    pub fn with_ruleset(mut self, ruleset: Ruleset) -> Self {
+        self.ruleset = ruleset;
+        self
+    }
+
+    // The last player is a dummy (2 players variant): it competes for points
+    // but point tiers are chosen by the count of real players.
+    pub fn with_dummy(mut self) -> Self {
+        self.with_dummy = true;
+        self
+    }
+
+    fn get_players_count(&self) -> usize {
+        if self.with_dummy {
+            self.states.len() - 1
+        } else {
+            self.states.len()
         }
     }
 
+    // Test helper: the n-th card of each player is taken as played on turn n,
+    // real games go through apply_turn as a player can play 2 cards in a turn.
+    #[cfg(test)]
+    pub(crate) fn apply_cards(&mut self, played_cards: &[CardVec]) {
+        let turns = played_cards.iter().map(|c| c.len()).max().unwrap_or(0);
+        for turn in 0..turns {
+            let played_now = played_cards
+                .iter()
+                .map(|cards| cards.get(turn).into_iter().cloned().collect())
+                .collect::<Vec<CardVec>>();
+            self.apply_turn(&played_now);
+        }
+    }
+
+    // Applies cards played by all players in a single turn and returns
+    // scoring events triggered by that turn.
+    pub fn apply_turn(&mut self, played_now: &[CardVec]) -> Vec<ScoringEvent> {
+        for (state, cards) in self.states.iter_mut().zip(played_now.iter()) {
+            for card in cards {
+                state.apply_card(*card);
+            }
+        }
+
+        let mut events = Vec::new();
+        self.score_uramaki_turn(&mut events);
+        events
+    }
+
     pub fn apply_card(&mut self, idx: usize, card: Card) {
         self.states[idx].apply_card(card);
     }
 
+    // Removes a card that left the table (e.g. used chopsticks).
+    pub fn remove_card(&mut self, idx: usize, card: Card) {
+        self.states[idx].remove_card(card);
+    }
+
+    // Replaces played card (at position in order of playing) with a face down marker.
+    pub fn flip_card(&mut self, idx: usize, pos: usize, marker: Card) {
+        self.states[idx].flip_card(pos, marker);
+    }
+
+    pub fn get_wasabi_slots(&self, idx: usize) -> Vec<Option<Card>> {
+        cards::get_wasabi_slots(&self.states[idx].played_cards)
+    }
+
+    pub fn get_empty_wasabi_count(&self, idx: usize) -> usize {
+        self.get_wasabi_slots(idx)
+            .iter()
+            .filter(|slot| slot.is_none())
+            .count()
+    }
+
     fn add_nigiri_points(&self, points: &mut [isize]) {
         for (p, state) in points.iter_mut().zip(self.states.iter()) {
-            *p += state.nigiri_points as isize;
+            *p += state.get_nigiri_points() as isize;
         }
     }
 
     fn add_maki_points(&self, points: &mut [isize]) {
-        let max_score = self.states.iter().map(|s| s.maki_score).max().unwrap_or(0);
-        if max_score == 0 {
-            // no states or no maki played -> no points
+        if self.ruleset == Ruleset::Original {
+            self.add_original_maki_points(points);
             return;
         }
 
         // maki point tiers as per rules
-        let maki_points = if self.states.len() <= 5 {
+        let maki_points = if self.get_players_count() <= 5 {
             PointCalculator::MAKI_POINTS_2_5_PLAYERS
         } else {
             PointCalculator::MAKI_POINTS_6_8_PLAYERS
         };
 
-        let ranks = {
-            let second_score = self
-                .states
-                .iter()
-                .map(|s| s.maki_score)
-                .filter(|s| *s != max_score)
-                .max()
-                .unwrap_or(0);
-
-            let third_score = self
-                .states
-                .iter()
-                .map(|s| s.maki_score)
-                .filter(|s| *s != max_score && *s != second_score)
-                .max()
-                .unwrap_or(0);
+        // players are ranked by their maki icons, tied players share the place
+        let scores = self
+            .states
+            .iter()
+            .map(|s| s.maki_score)
+            .collect::<Vec<usize>>();
+        let (ranked_points, _) = PointCalculator::get_ranked_points(&scores, &maki_points);
+        for (p, ranked) in points.iter_mut().zip(ranked_points) {
+            *p += ranked;
+        }
+    }
 
-            (max_score, second_score, third_score)
-        };
+    // Original rules: tied players split the points (rounded down),
+    // if there's a tie for the most no points are given for the second place.
+    fn add_original_maki_points(&self, points: &mut [isize]) {
+        let max_score = self.states.iter().map(|s| s.maki_score).max().unwrap_or(0);
+        if max_score == 0 {
+            return;
+        }
+        let max_count = self
+            .states
+            .iter()
+            .filter(|s| s.maki_score == max_score)
+            .count();
+        let second_score = self
+            .states
+            .iter()
+            .map(|s| s.maki_score)
+            .filter(|s| *s != max_score)
+            .max()
+            .unwrap_or(0);
+        let second_count = self
+            .states
+            .iter()
+            .filter(|s| s.maki_score == second_score)
+            .count();
 
-        // calculate points for each player
+        let [points_for_max, points_for_second] = PointCalculator::ORIGINAL_MAKI_POINTS;
         for (p, state) in points.iter_mut().zip(self.states.iter()) {
-            *p += match (state.maki_score, ranks) {
-                (0, (_, _, _)) => 0,
-                (s, (max, _, _)) if s == max => maki_points[0],
-                (s, (_, second, _)) if s == second => maki_points[1],
-                (s, (_, _, third)) if s == third => maki_points[2],
-                (_, _) => 0,
+            *p += match state.maki_score {
+                0 => 0,
+                s if s == max_score => points_for_max / max_count as isize,
+                s if s == second_score && max_count == 1 => {
+                    points_for_second / second_count as isize
+                }
+                _ => 0,
             }
         }
     }
@@ -174,7 +410,7 @@ impl PointCalculator {
     fn add_temaki_points(&self, points: &mut [isize]) {
         self.add_most_fewest_points(
             points,
-            if self.states.len() == 2 {
+            if self.get_players_count() == 2 {
                 PointCalculator::TEMAKI_2_PLAYERS
             } else {
                 PointCalculator::TEMAKI_3_8_PLAYERS
@@ -183,8 +419,53 @@ impl PointCalculator {
         )
     }
 
+    fn score_uramaki_turn(&mut self, events: &mut Vec<ScoringEvent>) {
+        let scores = self
+            .states
+            .iter()
+            .map(|s| s.uramaki_score_if_scoring())
+            .collect::<Vec<usize>>();
+        let awards = &PointCalculator::URAMAKI_POINTS[self.uramaki_position..];
+        let (ranked_points, places_taken) = PointCalculator::get_ranked_points(&scores, awards);
+
+        for (player_idx, (state, points)) in self.states.iter_mut().zip(ranked_points).enumerate() {
+            if state.has_uramaki_score() && points > 0 {
+                // scored uramaki are discarded, counting starts from 0 again
+                let uramaki_cards = state
+                    .played_cards
+                    .iter()
+                    .filter(|c| matches!(c, Uramaki(_)))
+                    .count();
+                state.remove_color(CardColor::UramakiGreen, uramaki_cards);
+                state.played_cards.retain(|c| !matches!(c, Uramaki(_)));
+                state.uramaki_points += points;
+                state.uramaki_score = 0;
+                events.push(ScoringEvent::Uramaki { player_idx, points });
+            }
+        }
+        self.uramaki_position += places_taken;
+    }
+
     fn add_uramaki_points(&self, end_of_round: bool, points: &mut [isize]) {
-        // TODO
+        self.add_simple_points(points, |state| state.uramaki_points);
+
+        if end_of_round {
+            let (ranked_points, _) = self.rank_remaining_uramaki();
+            for (p, ranked) in points.iter_mut().zip(ranked_points) {
+                *p += ranked;
+            }
+        }
+    }
+
+    // Remaining awards go to players with most uramaki icons at the end of the round.
+    fn rank_remaining_uramaki(&self) -> (Vec<isize>, usize) {
+        let scores = self
+            .states
+            .iter()
+            .map(|s| s.uramaki_score)
+            .collect::<Vec<usize>>();
+        let awards = &PointCalculator::URAMAKI_POINTS[self.uramaki_position..];
+        PointCalculator::get_ranked_points(&scores, awards)
     }
 
     fn add_dumpling_points(&self, points: &mut [isize]) {
@@ -227,16 +508,28 @@ impl PointCalculator {
     }
 
     fn add_onigiri_points(&self, points: &mut [isize]) {
-        self.add_simple_points(points, |state| match state.onigiri_present.len() {
-            0 => 0,
-            1 => 1,
-            2 => 4,
-            3 => 9,
-            4 => 16,
-            x => panic!(
-                "Invalid onigiri shapes count: {:?} for {:?}",
-                x, state.onigiri_present
-            ),
+        self.add_simple_points(points, |state| {
+            // each set consists of distinct shapes, duplicates start new sets
+            let sets_count = state.onigiri_counts.values().max().cloned().unwrap_or(0);
+            (0..sets_count)
+                .map(|set| {
+                    match state
+                        .onigiri_counts
+                        .values()
+                        .filter(|count| **count > set)
+                        .count()
+                    {
+                        1 => 1,
+                        2 => 4,
+                        3 => 9,
+                        4 => 16,
+                        x => panic!(
+                            "Invalid onigiri shapes count: {:?} for {:?}",
+                            x, state.onigiri_counts
+                        ),
+                    }
+                })
+                .sum()
         })
     }
 
@@ -251,17 +544,39 @@ impl PointCalculator {
     }
 
     fn add_soy_sauce_points(&self, points: &mut [isize]) {
-        // TODO
+        // soy sauce scores only if player has the most colors (ties count too)
+        let max_colors = self
+            .states
+            .iter()
+            .map(|s| s.color_counts.len())
+            .max()
+            .unwrap_or(0);
+        self.add_simple_points(points, |state| {
+            if state.color_counts.len() == max_colors {
+                state.soy_sauce_count as isize * 4
+            } else {
+                0
+            }
+        })
     }
 
     fn add_tea_points(&self, points: &mut [isize]) {
-        // TODO
+        // each tea scores 1 point per card in the biggest group of same color;
+        // tea is a (brown) card itself and wasabi is part of the (yellow) nigiri group
+        self.add_simple_points(points, |state| {
+            let biggest_group = state.color_counts.values().max().cloned().unwrap_or(0);
+            (state.tea_count * biggest_group) as isize
+        })
     }
 
     fn add_pudding_points(&self, points: &mut [isize]) {
+        if self.ruleset == Ruleset::Original {
+            self.add_original_pudding_points(points);
+            return;
+        }
         self.add_most_fewest_points(
             points,
-            if self.states.len() == 2 {
+            if self.get_players_count() == 2 {
                 PointCalculator::PUDDING_2_PLAYERS
             } else {
                 PointCalculator::PUDDING_3_8_PLAYERS
@@ -270,6 +585,51 @@ impl PointCalculator {
         )
     }
 
+    // Original rules: tied players split the points (rounded down),
+    // nobody scores if everybody is tied.
+    fn add_original_pudding_points(&self, points: &mut [isize]) {
+        let (points_for_max, points_for_min) = if self.get_players_count() == 2 {
+            PointCalculator::PUDDING_2_PLAYERS
+        } else {
+            PointCalculator::PUDDING_3_8_PLAYERS
+        };
+        let max_count = self
+            .states
+            .iter()
+            .map(|s| s.pudding_count)
+            .max()
+            .unwrap_or(0);
+        let min_count = self
+            .states
+            .iter()
+            .map(|s| s.pudding_count)
+            .min()
+            .unwrap_or(0);
+        if max_count == min_count {
+            return;
+        }
+        let players_with_max = self
+            .states
+            .iter()
+            .filter(|s| s.pudding_count == max_count)
+            .count();
+        let players_with_min = self
+            .states
+            .iter()
+            .filter(|s| s.pudding_count == min_count)
+            .count();
+
+        for (p, state) in points.iter_mut().zip(self.states.iter()) {
+            *p += if state.pudding_count == max_count {
+                points_for_max / players_with_max as isize
+            } else if state.pudding_count == min_count {
+                points_for_min / players_with_min as isize
+            } else {
+                0
+            }
+        }
+    }
+
     fn get_points_for_fruit_count(count: usize) -> isize {
         match count {
             0 => -2,
@@ -290,6 +650,29 @@ impl PointCalculator {
         });
     }
 
+    // Ranks players by score (highest first) and gives them points for their place.
+    // Tied players all get the points for their place and the following places
+    // are skipped. Players with score 0 are not ranked. Returns points for each
+    // player and the number of places taken.
+    fn get_ranked_points(scores: &[usize], points_for_places: &[isize]) -> (Vec<isize>, usize) {
+        let ranked = scores
+            .iter()
+            .map(|score| {
+                let place = scores.iter().filter(|other| *other > score).count();
+                match (score, points_for_places.get(place)) {
+                    (0, _) | (_, None) => 0,
+                    (_, Some(points)) => *points,
+                }
+            })
+            .collect();
+        let places_taken = scores
+            .iter()
+            .filter(|s| **s > 0)
+            .count()
+            .min(points_for_places.len());
+        (ranked, places_taken)
+    }
+
     fn add_simple_points<F>(&self, points: &mut [isize], points_fun: F)
     where
         F: Fn(&PointCalculatorPlayerState) -> isize,
@@ -307,8 +690,8 @@ impl PointCalculator {
     ) where
         F: Fn(&PointCalculatorPlayerState) -> usize,
     {
-        let max_count = self.states.iter().map(|s| accessor(s)).max().unwrap_or(0);
-        let min_count = self.states.iter().map(|s| accessor(s)).min().unwrap_or(0);
+        let max_count = self.states.iter().map(&accessor).max().unwrap_or(0);
+        let min_count = self.states.iter().map(&accessor).min().unwrap_or(0);
 
         for (p, state) in points.iter_mut().zip(self.states.iter()) {
             *p += if accessor(state) == max_count {
@@ -321,40 +704,404 @@ impl PointCalculator {
         }
     }
 
-    pub fn calculate_points(&self, menu: &Menu, end_of_round: bool) -> Vec<isize> {
-        let mut points = vec![0isize; self.states.len()];
+    // Points of each player split by scoring categories.
+    pub fn calculate_breakdown(&self, menu: &Menu, end_of_round: bool) -> Vec<ScoreBreakdown> {
+        let mut breakdowns = vec![ScoreBreakdown::default(); self.states.len()];
+        let mut add_points = |field: fn(&mut ScoreBreakdown) -> &mut isize,
+                              add: &dyn Fn(&mut [isize])| {
+            let mut points = vec![0isize; breakdowns.len()];
+            add(&mut points);
+            for (breakdown, p) in breakdowns.iter_mut().zip(points) {
+                *field(breakdown) += p;
+            }
+        };
 
-        self.add_nigiri_points(&mut points);
-        self.add_maki_points(&mut points);
+        add_points(|b| &mut b.nigiri, &|points| self.add_nigiri_points(points));
+        add_points(|b| &mut b.maki, &|points| self.add_maki_points(points));
         if menu.contains(&Temaki) {
-            self.add_temaki_points(&mut points);
+            add_points(|b| &mut b.temaki, &|points| self.add_temaki_points(points));
         }
-        self.add_uramaki_points(end_of_round, &mut points);
-        self.add_dumpling_points(&mut points);
-        self.add_edamame_points(&mut points);
-        self.add_eel_points(&mut points);
-        self.add_onigiri_points(&mut points);
-        self.add_simple_points(&mut points, |state| (state.miso_count as isize) * 3);
-        self.add_simple_points(&mut points, |state| (state.sashimi_count as isize) / 3 * 10);
-        self.add_simple_points(&mut points, |state| (state.tempura_count as isize) / 2 * 5);
-        self.add_tofu_points(&mut points);
-        self.add_soy_sauce_points(&mut points);
-        self.add_simple_points(&mut points, |state| (state.taken_out_count as isize) * 2);
-        self.add_tea_points(&mut points);
-        self.add_simple_points(&mut points, |state| {
-            (state.ice_cream_count as isize) / 4 * 12
+        add_points(|b| &mut b.uramaki, &|points| {
+            self.add_uramaki_points(end_of_round, points)
+        });
+        add_points(|b| &mut b.dumpling, &|points| {
+            self.add_dumpling_points(points)
+        });
+        add_points(|b| &mut b.edamame, &|points| {
+            self.add_edamame_points(points)
+        });
+        add_points(|b| &mut b.eel, &|points| self.add_eel_points(points));
+        add_points(|b| &mut b.onigiri, &|points| {
+            self.add_onigiri_points(points)
+        });
+        add_points(|b| &mut b.miso, &|points| {
+            self.add_simple_points(points, |state| (state.miso_count as isize) * 3)
+        });
+        add_points(|b| &mut b.sashimi, &|points| {
+            self.add_simple_points(points, |state| (state.sashimi_count as isize) / 3 * 10)
+        });
+        add_points(|b| &mut b.tempura, &|points| {
+            self.add_simple_points(points, |state| (state.tempura_count as isize) / 2 * 5)
+        });
+        add_points(|b| &mut b.tofu, &|points| self.add_tofu_points(points));
+        add_points(|b| &mut b.soy_sauce, &|points| {
+            self.add_soy_sauce_points(points)
+        });
+        add_points(|b| &mut b.takeout, &|points| {
+            self.add_simple_points(points, |state| (state.taken_out_count as isize) * 2)
+        });
+        add_points(|b| &mut b.tea, &|points| self.add_tea_points(points));
+        add_points(|b| &mut b.ice_cream, &|points| {
+            self.add_simple_points(points, |state| (state.ice_cream_count as isize) / 4 * 12)
         });
         if menu.contains(&Pudding) {
-            self.add_pudding_points(&mut points);
+            add_points(|b| &mut b.pudding, &|points| {
+                self.add_pudding_points(points)
+            });
         }
         if cards::has_fruit(menu) {
-            self.add_fruit_points(&mut points);
+            add_points(|b| &mut b.fruit, &|points| self.add_fruit_points(points));
         }
 
-        points
+        breakdowns
+    }
+
+    pub fn calculate_points(&self, menu: &Menu, end_of_round: bool) -> Vec<isize> {
+        self.calculate_breakdown(menu, end_of_round)
+            .iter()
+            .map(|breakdown| breakdown.total())
+            .collect()
     }
 
-    pub fn has_uramaki_scores(&self) -> usize {
-        self.states.iter().filter(|s| s.has_uramaki_score()).count()
+    pub fn uramaki_position(&self) -> usize {
+        self.uramaki_position
+    }
+
+    // Position the next round starts from: awards handed out at the end
+    // of the round are used up just like the ones won during it.
+    pub fn uramaki_position_after_round(&self) -> usize {
+        let (_, places_taken) = self.rank_remaining_uramaki();
+        self.uramaki_position + places_taken
+    }
+}
+
+#[test]
+fn test_uramaki_awards_in_order() {
+    let menu = [Uramaki(3), Uramaki(4), Uramaki(5)]
+        .iter()
+        .cloned()
+        .collect::<Menu>();
+    let mut calculator = PointCalculator::with_capacity(3, 0);
+    calculator.apply_cards(&[
+        vec![Uramaki(3), Uramaki(3), Uramaki(3), Uramaki(3)],
+        vec![Uramaki(5), Uramaki(5), Uramaki(3)],
+        vec![Uramaki(4)],
+    ]);
+    assert_eq!(calculator.uramaki_position(), 2);
+    assert_eq!(calculator.calculate_points(&menu, false), vec![5, 8, 0]);
+    assert_eq!(calculator.calculate_points(&menu, true), vec![5, 8, 2]);
+}
+
+#[test]
+fn test_apply_turn_events() {
+    let mut calculator = PointCalculator::with_capacity(2, 0);
+    assert_eq!(
+        calculator.apply_turn(&[vec![Uramaki(5)], vec![Uramaki(4)]]),
+        vec![]
+    );
+    assert_eq!(
+        calculator.apply_turn(&[vec![Uramaki(5)], vec![Uramaki(3)]]),
+        vec![ScoringEvent::Uramaki {
+            player_idx: 0,
+            points: 8
+        }]
+    );
+    assert_eq!(
+        calculator.apply_turn(&[vec![], vec![Uramaki(3)]]),
+        vec![ScoringEvent::Uramaki {
+            player_idx: 1,
+            points: 5
+        }]
+    );
+}
+
+#[test]
+fn test_uramaki_position_carried() {
+    let menu = [Uramaki(3), Uramaki(4), Uramaki(5)]
+        .iter()
+        .cloned()
+        .collect::<Menu>();
+    let mut first_round = PointCalculator::with_capacity(3, 0);
+    first_round.apply_cards(&[vec![Uramaki(5), Uramaki(5)], vec![Uramaki(3)], vec![]]);
+    assert_eq!(first_round.calculate_points(&menu, true), vec![8, 5, 0]);
+    assert_eq!(first_round.uramaki_position(), 1);
+    assert_eq!(first_round.uramaki_position_after_round(), 2);
+
+    // the next round continues from the last award
+    let mut second_round =
+        PointCalculator::with_capacity(3, first_round.uramaki_position_after_round());
+    second_round.apply_cards(&[
+        vec![Uramaki(3)],
+        vec![Uramaki(5), Uramaki(5)],
+        vec![Uramaki(4)],
+    ]);
+    assert_eq!(second_round.calculate_points(&menu, true), vec![0, 2, 0]);
+    assert_eq!(second_round.uramaki_position_after_round(), 3);
+
+    // all awards are used up, uramaki don't score for the rest of the game
+    let mut third_round =
+        PointCalculator::with_capacity(3, second_round.uramaki_position_after_round());
+    third_round.apply_cards(&[vec![Uramaki(5), Uramaki(5)], vec![Uramaki(3)], vec![]]);
+    assert_eq!(third_round.calculate_points(&menu, true), vec![0, 0, 0]);
+}
+
+#[test]
+fn test_uramaki_same_turn() {
+    let menu = [Uramaki(4), Uramaki(5)].iter().cloned().collect::<Menu>();
+    let mut calculator = PointCalculator::with_capacity(4, 0);
+    calculator.apply_cards(&[
+        vec![Uramaki(4), Uramaki(4), Uramaki(5)],
+        vec![Uramaki(4), Uramaki(4), Uramaki(4)],
+        vec![Uramaki(5), Uramaki(5)],
+        vec![Uramaki(4), Uramaki(4)],
+    ]);
+    // 3rd player crosses alone on turn 2, the first two cross on turn 3
+    // with 13 and 12 icons -> higher count gets the better award
+    assert_eq!(calculator.calculate_points(&menu, true), vec![5, 2, 8, 0]);
+
+    let mut calculator = PointCalculator::with_capacity(3, 0);
+    calculator.apply_cards(&[
+        vec![Uramaki(5), Uramaki(5)],
+        vec![Uramaki(5), Uramaki(5)],
+        vec![Uramaki(4), Uramaki(4), Uramaki(4)],
+    ]);
+    // tie -> both get first award, second award is skipped
+    assert_eq!(calculator.uramaki_position(), 3);
+    assert_eq!(calculator.calculate_points(&menu, true), vec![8, 8, 2]);
+}
+
+#[test]
+fn test_score_breakdown() {
+    let menu = [Nigiri(3), Maki(1), Maki(3), Tempura, Pudding]
+        .iter()
+        .cloned()
+        .collect::<Menu>();
+    let mut calculator = PointCalculator::with_capacity(2, 0);
+    calculator.apply_cards(&[
+        vec![Nigiri(3), Maki(3), Tempura, Tempura],
+        vec![Maki(1), Pudding],
+    ]);
+    let breakdowns = calculator.calculate_breakdown(&menu, true);
+    assert_eq!(
+        breakdowns[0],
+        ScoreBreakdown {
+            nigiri: 3,
+            maki: 6,
+            tempura: 5,
+            ..ScoreBreakdown::default()
+        }
+    );
+    assert_eq!(
+        breakdowns[1],
+        ScoreBreakdown {
+            maki: 3,
+            pudding: 6,
+            ..ScoreBreakdown::default()
+        }
+    );
+    assert_eq!(calculator.calculate_points(&menu, true), vec![14, 9]);
+}
+
+#[test]
+fn test_soy_sauce() {
+    let menu = [Tempura, Sashimi, SoySauce]
+        .iter()
+        .cloned()
+        .collect::<Menu>();
+    let mut calculator = PointCalculator::with_capacity(3, 0);
+    calculator.apply_cards(&[
+        vec![SoySauce, SoySauce, Tempura],
+        vec![SoySauce, Sashimi, Tempura],
+        vec![Sashimi, Tempura, Tempura],
+    ]);
+    assert_eq!(calculator.calculate_points(&menu, true), vec![0, 4, 5]);
+
+    let mut calculator = PointCalculator::with_capacity(2, 0);
+    calculator.apply_cards(&[vec![SoySauce, Sashimi], vec![SoySauce, Tempura]]);
+    assert_eq!(calculator.calculate_points(&menu, true), vec![4, 4]);
+}
+
+#[test]
+fn test_tea() {
+    let menu = [Nigiri(1), Wasabi, Tempura, Tea]
+        .iter()
+        .cloned()
+        .collect::<Menu>();
+    let mut calculator = PointCalculator::with_capacity(3, 0);
+    calculator.apply_cards(&[
+        // wasabi + 2 nigiri -> group of 3
+        vec![Tea, Wasabi, Nigiri(1), Nigiri(1), Tempura],
+        // tea counts itself -> group of 2 teas
+        vec![Tea, Tea, Tempura],
+        vec![Tempura],
+    ]);
+    assert_eq!(calculator.calculate_points(&menu, true), vec![3 + 4, 4, 0]);
+}
+
+#[test]
+fn test_takeout_box() {
+    let menu = [Nigiri(2), Wasabi, Tea, TakeoutBox(10)]
+        .iter()
+        .cloned()
+        .collect::<Menu>();
+    let mut calculator = PointCalculator::with_capacity(2, 0);
+    calculator.apply_cards(&[
+        vec![Wasabi, Nigiri(2), Tea, Tea, Nigiri(2)],
+        vec![Wasabi, Nigiri(2)],
+    ]);
+    assert_eq!(
+        calculator.calculate_points(&menu, true),
+        vec![6 + 2 + 2 * 3, 6]
+    );
+
+    // flipping the wasabi leaves its nigiri without it,
+    // flipped cards have no color
+    calculator.flip_card(0, 0, TakeoutBox(10));
+    calculator.flip_card(0, 2, TakeoutBox(10));
+    assert_eq!(
+        calculator.calculate_points(&menu, true),
+        vec![2 + 2 + 2 + 2 * 2, 6]
+    );
+}
+
+#[test]
+fn test_wasabi_slots() {
+    let menu = [Nigiri(1), Nigiri(3), Wasabi]
+        .iter()
+        .cloned()
+        .collect::<Menu>();
+    let mut calculator = PointCalculator::with_capacity(2, 0);
+    calculator.apply_cards(&[
+        vec![Nigiri(1), Wasabi, Wasabi, Nigiri(3), Wasabi],
+        vec![Wasabi, Wasabi, Nigiri(1), Nigiri(3)],
+    ]);
+    assert_eq!(
+        calculator.get_wasabi_slots(0),
+        vec![Some(Nigiri(3)), None, None]
+    );
+    assert_eq!(calculator.get_empty_wasabi_count(0), 2);
+    assert_eq!(calculator.get_empty_wasabi_count(1), 0);
+    assert_eq!(calculator.calculate_points(&menu, true), vec![1 + 9, 3 + 9]);
+
+    // nigiri played later fills the first empty wasabi
+    calculator.flip_card(1, 2, TakeoutBox(10));
+    calculator.apply_card(1, Nigiri(3));
+    assert_eq!(
+        calculator.get_wasabi_slots(1),
+        vec![Some(Nigiri(3)), Some(Nigiri(3))]
+    );
+}
+
+#[cfg(test)]
+fn maki_points(maki_scores: &[usize]) -> Vec<isize> {
+    let menu = [Maki(1), Maki(2), Maki(3)]
+        .iter()
+        .cloned()
+        .collect::<Menu>();
+    let mut calculator = PointCalculator::with_capacity(maki_scores.len(), 0);
+    for (idx, score) in maki_scores.iter().enumerate() {
+        for _ in 0..*score {
+            calculator.apply_card(idx, Maki(1));
+        }
     }
+    calculator.calculate_points(&menu, true)
+}
+
+#[test]
+fn test_maki() {
+    assert_eq!(maki_points(&[0, 0, 0]), vec![0, 0, 0]);
+    assert_eq!(maki_points(&[5, 3, 1, 0]), vec![6, 3, 0, 0]);
+    assert_eq!(
+        maki_points(&[5, 3, 1, 0, 3, 2, 1]),
+        vec![6, 4, 0, 0, 4, 0, 0]
+    );
+    assert_eq!(
+        maki_points(&[5, 4, 1, 0, 3, 2, 1]),
+        vec![6, 4, 0, 0, 2, 0, 0]
+    );
+}
+
+#[test]
+fn test_maki_ties() {
+    // tied players get points for their place, next places are skipped
+    assert_eq!(maki_points(&[4, 4, 1]), vec![6, 6, 0]);
+    assert_eq!(maki_points(&[4, 4, 1, 0, 0, 0]), vec![6, 6, 2, 0, 0, 0]);
+    assert_eq!(maki_points(&[4, 4, 4, 1, 0, 0]), vec![6, 6, 6, 0, 0, 0]);
+    assert_eq!(maki_points(&[4, 2, 2, 1, 0, 0]), vec![6, 4, 4, 0, 0, 0]);
+    assert_eq!(maki_points(&[4, 2, 2]), vec![6, 3, 3]);
+}
+
+#[test]
+fn test_onigiri() {
+    let menu = [Onigiri(false, false)].iter().cloned().collect::<Menu>();
+    let mut calculator = PointCalculator::with_capacity(3, 0);
+    calculator.apply_cards(&[
+        vec![
+            Onigiri(false, false),
+            Onigiri(true, false),
+            Onigiri(false, false),
+        ],
+        vec![
+            Onigiri(false, false),
+            Onigiri(true, false),
+            Onigiri(false, true),
+            Onigiri(true, true),
+            Onigiri(true, true),
+            Onigiri(false, true),
+        ],
+        vec![
+            Onigiri(true, true),
+            Onigiri(true, true),
+            Onigiri(true, true),
+        ],
+    ]);
+    assert_eq!(
+        calculator.calculate_points(&menu, true),
+        vec![4 + 1, 16 + 4, 3]
+    );
+}
+
+#[test]
+fn test_dummy() {
+    let menu = [Temaki].iter().cloned().collect::<Menu>();
+    let mut calculator = PointCalculator::with_capacity(3, 0).with_dummy();
+    calculator.apply_cards(&[
+        vec![Temaki, Temaki],
+        vec![Temaki],
+        vec![Temaki, Temaki, Temaki],
+    ]);
+    // dummy has the most, nobody loses points for the fewest with 2 players
+    assert_eq!(calculator.calculate_points(&menu, true), vec![0, 0, 4]);
+}
+
+#[test]
+fn test_original_rules() {
+    let menu = cards::ORIGINAL_CARDS.iter().cloned().collect::<Menu>();
+    let mut calculator = PointCalculator::with_capacity(4, 0).with_ruleset(Ruleset::Original);
+    calculator.apply_cards(&[
+        vec![Maki(3), Pudding, Pudding],
+        vec![Maki(3), Pudding],
+        vec![Maki(2), Pudding, Pudding],
+        vec![Maki(1)],
+    ]);
+    // tie for the most maki -> 6 split, no points for the second place;
+    // tie for the most puddings -> 6 split
+    assert_eq!(
+        calculator.calculate_points(&menu, true),
+        vec![3 + 3, 3, 3, -6]
+    );
+
+    let mut calculator = PointCalculator::with_capacity(4, 0).with_ruleset(Ruleset::Original);
+    calculator.apply_cards(&[vec![Maki(3)], vec![Maki(2)], vec![Maki(2)], vec![Maki(1)]]);
+    assert_eq!(calculator.calculate_points(&menu, true), vec![6, 1, 1, 0]);
 }